@@ -0,0 +1 @@
+../tools/gen-words/src/char_counts.rs
\ No newline at end of file
@@ -0,0 +1,159 @@
+// Settings only the game itself has any use for, on top of the ones it shares
+// with the server, and its command line
+
+use colors::ColorScheme;
+use config::{self, de_parse, parse_arg, Config};
+use keymap::Keymap;
+use std::{
+  collections::VecDeque,
+  path::{Path, PathBuf},
+};
+use {ErrorKind, Result};
+
+pub static USAGE: &str = "usage: omelette [options]
+       omelette [options] solve <letters>
+       omelette [options] ladder
+       omelette [options] join <host[:port]>
+       omelette [options] keys
+
+options:
+  -c, --config <file>    read settings from <file> instead of the default
+  -d, --db <file>        use the word database stored in <file>
+  -l, --len <n|min-max>  play words of length n, or a random length in a range
+  -m, --mode <mode>      start in the given game mode (normal, blitz)
+  -t, --time <seconds>   set the time limit for blitz rounds
+  -D, --difficulty <lvl> pick words by how common they are (easy, normal, hard)
+  -p, --players <names>  take turns with 2 to 4 players, e.g. ann,bo,cy
+  -n, --name <name>      the name to join a server with (defaults to $USER)
+  -s, --seed <n>         generate puzzles from the given seed
+      --daily            play the puzzles of the day (the same for everyone)
+      --colors <scheme>  use a color scheme (dark, terminal, light)
+      --log <file>       write debug output to <file>
+      --headless         play over stdin/stdout with JSON commands
+  -h, --help             print this message and exit
+
+commands:
+  solve <letters>        list every word in the set for <letters> and exit
+  ladder                 play word ladders instead of finding words
+  join <host[:port]>     race other players on an omelette-server
+  keys                   list the active key bindings and exit";
+
+// These are read from the same file as the shared settings
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ClientConfig {
+  #[serde(flatten)]
+  pub config: Config,
+  #[serde(deserialize_with = "de_parse")]
+  pub colors: Option<ColorScheme>,
+  pub keys: Option<Keymap>,
+}
+
+impl ClientConfig {
+  pub fn load(path: Option<&Path>) -> Result<Self> {
+    config::load(path)
+  }
+
+  // Any values set in other take precedence
+  pub fn merge(self, other: ClientConfig) -> ClientConfig {
+    ClientConfig {
+      config: self.config.merge(other.config),
+      colors: other.colors.or(self.colors),
+      keys: other.keys.or(self.keys),
+    }
+  }
+
+  pub fn colors(&self) -> ColorScheme {
+    self.colors.unwrap_or_default()
+  }
+
+  pub fn keymap(&self) -> Keymap {
+    self.keys.clone().unwrap_or_default()
+  }
+}
+
+pub struct Args {
+  pub help: bool,
+  pub headless: bool,
+  pub ladder: bool,
+  pub keys: bool,
+  pub solve: Option<String>,
+  pub join: Option<String>, // A server address
+  pub name: Option<String>,
+  pub config_path: Option<PathBuf>,
+  pub seed: Option<u64>,
+  pub config: ClientConfig,
+}
+
+impl Args {
+  pub fn parse<I>(args: I) -> Result<Self>
+  where
+    I: IntoIterator<Item = String>,
+  {
+    let mut args: VecDeque<_> = args.into_iter().collect();
+    args.pop_front(); // drop argv[0]
+
+    let mut ret = Args {
+      help: false,
+      headless: false,
+      ladder: false,
+      keys: false,
+      solve: None,
+      join: None,
+      name: None,
+      config_path: None,
+      seed: None,
+      config: Default::default(),
+    };
+
+    while let Some(arg) = args.pop_front() {
+      let client = &mut ret.config;
+      let config = &mut client.config;
+
+      match &*arg {
+        "-h" | "--help" => ret.help = true,
+        "-c" | "--config" => {
+          ret.config_path =
+            Some(parse_arg::<String>(&mut args, "a filename")?.into())
+        }
+        "-d" | "--db" => {
+          config.db_path =
+            Some(parse_arg::<String>(&mut args, "a filename")?.into())
+        }
+        "-l" | "--len" => {
+          config.word_len = Some(parse_arg(&mut args, "a word length")?)
+        }
+        "-m" | "--mode" => {
+          config.mode = Some(parse_arg(&mut args, "a game mode")?)
+        }
+        "-t" | "--time" => {
+          config.time_limit = Some(parse_arg(&mut args, "a number of seconds")?)
+        }
+        "-D" | "--difficulty" => {
+          config.difficulty = Some(parse_arg(&mut args, "a difficulty")?)
+        }
+        "-p" | "--players" => {
+          config.players = Some(parse_arg(&mut args, "player names")?)
+        }
+        "-n" | "--name" => ret.name = Some(parse_arg(&mut args, "a name")?),
+        "-s" | "--seed" => ret.seed = Some(parse_arg(&mut args, "a seed")?),
+        "--daily" => config.daily = Some(true),
+        "--headless" => ret.headless = true,
+        "ladder" => ret.ladder = true,
+        "keys" => ret.keys = true,
+        "solve" => ret.solve = Some(parse_arg(&mut args, "letters to solve")?),
+        "join" => ret.join = Some(parse_arg(&mut args, "a server address")?),
+        "--colors" => {
+          client.colors = Some(parse_arg(&mut args, "a color scheme")?)
+        }
+        "--log" => {
+          config.debug_log =
+            Some(parse_arg::<String>(&mut args, "a filename")?.into())
+        }
+        a => return Err(ErrorKind::UnknownArg(a.into()).into()),
+      }
+    }
+
+    Ok(ret)
+  }
+}
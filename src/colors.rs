@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+pub type ColorPair = (i16, i16); // (foreground, background)
+
+#[derive(Clone, Copy)]
+pub struct ColorScheme {
+  pub ghost: ColorPair,
+  pub bad_ghost: ColorPair,
+  pub auto_ghost: ColorPair,
+  pub reveal: ColorPair,
+  pub hl: ColorPair,
+  pub hint: ColorPair,
+  pub best: ColorPair,
+  pub clock_low: ColorPair,
+  pub players: [ColorPair; 4], // For hot-seat play, one per player
+}
+
+// NB: -1 refers to the terminal's default color
+
+impl ColorScheme {
+  pub fn dark() -> Self {
+    Self {
+      ghost: (2, 0),
+      bad_ghost: (1, 0),
+      auto_ghost: (3, 0),
+      reveal: (3, 0),
+      hl: (2, 0),
+      hint: (5, 0),
+      best: (6, 0),
+      clock_low: (1, 0),
+      players: [(6, 0), (5, 0), (4, 0), (7, 0)],
+    }
+  }
+
+  pub fn terminal() -> Self {
+    Self {
+      ghost: (2, -1),
+      bad_ghost: (1, -1),
+      auto_ghost: (3, -1),
+      reveal: (3, -1),
+      hl: (2, -1),
+      hint: (5, -1),
+      best: (6, -1),
+      clock_low: (1, -1),
+      players: [(6, -1), (5, -1), (4, -1), (7, -1)],
+    }
+  }
+
+  pub fn light() -> Self {
+    Self {
+      ghost: (4, -1),
+      bad_ghost: (1, -1),
+      auto_ghost: (5, -1),
+      reveal: (5, -1),
+      hl: (4, -1),
+      hint: (6, -1),
+      best: (2, -1),
+      clock_low: (1, -1),
+      players: [(4, -1), (1, -1), (2, -1), (6, -1)],
+    }
+  }
+}
+
+impl Default for ColorScheme {
+  fn default() -> Self {
+    Self::dark()
+  }
+}
+
+impl FromStr for ColorScheme {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, String> {
+    match s {
+      "dark" | "default" => Ok(Self::dark()),
+      "terminal" => Ok(Self::terminal()),
+      "light" => Ok(Self::light()),
+      s => Err(format!("unknown color scheme '{}'", s)),
+    }
+  }
+}
@@ -0,0 +1,230 @@
+use difficulty::Difficulty;
+use dirs;
+use mode::GameMode;
+use players::PlayerNames;
+use serde::de::{self, DeserializeOwned, Deserializer, Visitor};
+use serde_json;
+use std::{
+  collections::VecDeque,
+  fmt,
+  fs::{self, File},
+  io::{self, BufReader},
+  marker::PhantomData,
+  path::{Path, PathBuf},
+  result::Result as StdResult,
+  str::FromStr,
+  time::Duration,
+};
+use {ErrorKind, Result};
+
+static APP_DIR: &str = "omelette";
+static CONFIG_FILE: &str = "config.json";
+static DB_FILE: &str = "words.sqlite3";
+static FALLBACK_DB_PATH: &str = "etc/words.sqlite3";
+static SCORES_FILE: &str = "scores.json";
+static STATS_FILE: &str = "stats.sqlite3";
+static SAVE_FILE: &str = "save.json";
+
+pub fn config_dir() -> Option<PathBuf> {
+  dirs::config_dir().map(|d| d.join(APP_DIR))
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+  dirs::data_dir().map(|d| d.join(APP_DIR))
+}
+
+// Returns a path to a file in the data directory, creating the directory if
+// necessary.  If there's no data directory, the file goes in etc/.
+pub fn data_file(name: &str) -> io::Result<PathBuf> {
+  match data_dir() {
+    Some(dir) => {
+      fs::create_dir_all(&dir)?;
+      Ok(dir.join(name))
+    }
+    None => Ok(Path::new("etc").join(name)),
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct LenRange {
+  pub min: usize,
+  pub max: usize,
+}
+
+impl FromStr for LenRange {
+  type Err = String;
+
+  fn from_str(s: &str) -> StdResult<Self, String> {
+    let parse = |s: &str| {
+      s.trim()
+        .parse()
+        .map_err(|e| format!("invalid word length '{}': {}", s, e))
+    };
+
+    let (min, max) = match s.find('-') {
+      Some(i) => (parse(&s[..i])?, parse(&s[i + 1..])?),
+      None => {
+        let len = parse(s)?;
+        (len, len)
+      }
+    };
+
+    if min > max {
+      return Err(format!("invalid word length range '{}'", s));
+    }
+
+    Ok(Self { min, max })
+  }
+}
+
+struct ParseVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for ParseVisitor<T>
+where
+  T: FromStr,
+  T::Err: fmt::Display,
+{
+  type Value = Option<T>;
+
+  fn expecting(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str("a string or a number")
+  }
+
+  fn visit_str<E: de::Error>(self, val: &str) -> StdResult<Option<T>, E> {
+    val.parse().map(Some).map_err(E::custom)
+  }
+
+  fn visit_u64<E: de::Error>(self, val: u64) -> StdResult<Option<T>, E> {
+    self.visit_str(&val.to_string())
+  }
+
+  fn visit_unit<E: de::Error>(self) -> StdResult<Option<T>, E> {
+    Ok(None)
+  }
+}
+
+pub fn de_parse<'de, D, T>(de: D) -> StdResult<Option<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: FromStr,
+  T::Err: fmt::Display,
+{
+  de.deserialize_any(ParseVisitor(PhantomData))
+}
+
+// Reads settings from a config file.  A missing file is only an error if the
+// path was given explicitly.  Settings T doesn't know about are skipped, so
+// that the game and the server can share a file.
+pub fn load<T>(path: Option<&Path>) -> Result<T>
+where
+  T: DeserializeOwned + Default,
+{
+  let (path, required) = match path {
+    Some(p) => (p.to_path_buf(), true),
+    None => match config_dir() {
+      Some(d) => (d.join(CONFIG_FILE), false),
+      None => return Ok(Default::default()),
+    },
+  };
+
+  let file = match File::open(&path) {
+    Ok(f) => f,
+    Err(ref e) if !required && e.kind() == io::ErrorKind::NotFound => {
+      return Ok(Default::default())
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+    ErrorKind::Config(path.display().to_string(), e.to_string()).into()
+  })
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+  pub db_path: Option<PathBuf>,
+  #[serde(deserialize_with = "de_parse")]
+  pub word_len: Option<LenRange>,
+  pub debug_log: Option<PathBuf>,
+  #[serde(deserialize_with = "de_parse")]
+  pub mode: Option<GameMode>,
+  pub time_limit: Option<u64>, // In seconds
+  #[serde(deserialize_with = "de_parse")]
+  pub difficulty: Option<Difficulty>,
+  #[serde(deserialize_with = "de_parse")]
+  pub players: Option<PlayerNames>,
+  pub daily: Option<bool>,
+}
+
+impl Config {
+  pub fn load(path: Option<&Path>) -> Result<Self> {
+    load(path)
+  }
+
+  // Any values set in other take precedence
+  pub fn merge(self, other: Config) -> Config {
+    Config {
+      db_path: other.db_path.or(self.db_path),
+      word_len: other.word_len.or(self.word_len),
+      debug_log: other.debug_log.or(self.debug_log),
+      mode: other.mode.or(self.mode),
+      time_limit: other.time_limit.or(self.time_limit),
+      difficulty: other.difficulty.or(self.difficulty),
+      players: other.players.or(self.players),
+      daily: other.daily.or(self.daily),
+    }
+  }
+
+  pub fn db_path(&self) -> PathBuf {
+    if let Some(ref p) = self.db_path {
+      return p.clone();
+    }
+
+    match data_dir().map(|d| d.join(DB_FILE)) {
+      Some(ref p) if p.exists() => p.clone(),
+      _ => PathBuf::from(FALLBACK_DB_PATH),
+    }
+  }
+
+  pub fn scores_path(&self) -> io::Result<PathBuf> {
+    data_file(SCORES_FILE)
+  }
+
+  pub fn stats_path(&self) -> io::Result<PathBuf> {
+    data_file(STATS_FILE)
+  }
+
+  pub fn save_path(&self) -> io::Result<PathBuf> {
+    data_file(SAVE_FILE)
+  }
+
+  pub fn daily(&self) -> bool {
+    self.daily.unwrap_or(false)
+  }
+
+  pub fn difficulty(&self) -> Difficulty {
+    self.difficulty.unwrap_or_default()
+  }
+
+  pub fn mode(&self) -> GameMode {
+    match (self.mode.unwrap_or(GameMode::Normal), self.time_limit) {
+      (GameMode::Blitz(_), Some(t)) => GameMode::Blitz(Duration::from_secs(t)),
+      (m, _) => m,
+    }
+  }
+}
+
+// Pops the value of an option off the front of args
+pub fn parse_arg<T>(args: &mut VecDeque<String>, expect: &str) -> Result<T>
+where
+  T: FromStr,
+  T::Err: fmt::Display,
+{
+  match args.pop_front() {
+    Some(a) => a,
+    None => return Err(ErrorKind::InvalidArg(expect.into()).into()),
+  }
+  .parse()
+  .map_err(|e: T::Err| ErrorKind::ArgParse(e.to_string()).into())
+}
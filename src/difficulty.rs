@@ -0,0 +1,59 @@
+use std::str::FromStr;
+
+// Commonness scores and set difficulties both run from 0 to 100, as rated by
+// gen-words.  Word databases without them play the same at every difficulty.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Difficulty {
+  Easy,
+  Normal,
+  Hard,
+}
+
+impl Difficulty {
+  // The hardest sets offered as keys
+  pub fn max_set_difficulty(&self) -> Option<i32> {
+    match self {
+      Difficulty::Easy => Some(40),
+      Difficulty::Normal => Some(70),
+      Difficulty::Hard => None,
+    }
+  }
+
+  // Rarer words than this are left out of the set, leaving them as bonus words
+  pub fn min_commonness(&self) -> Option<i32> {
+    match self {
+      Difficulty::Easy => Some(50),
+      Difficulty::Normal => Some(20),
+      Difficulty::Hard => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Difficulty::Easy => "easy",
+      Difficulty::Normal => "normal",
+      Difficulty::Hard => "hard",
+    }
+  }
+}
+
+// Hard leaves every set and word in, the way the game played before there were
+// difficulties
+impl Default for Difficulty {
+  fn default() -> Self {
+    Difficulty::Hard
+  }
+}
+
+impl FromStr for Difficulty {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, String> {
+    match s {
+      "easy" => Ok(Difficulty::Easy),
+      "normal" => Ok(Difficulty::Normal),
+      "hard" => Ok(Difficulty::Hard),
+      s => Err(format!("unknown difficulty '{}'", s)),
+    }
+  }
+}
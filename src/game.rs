@@ -0,0 +1,708 @@
+// The rules of a round, independent of any frontend
+
+use char_counts::*;
+use difficulty::Difficulty;
+use markov::{self, FreqTable, Markov};
+use rand::Rng;
+use score::RoundScore;
+use seed::{self, GameRng};
+use std::{
+  cmp,
+  collections::{BTreeMap, BTreeSet, HashSet},
+  time::Duration,
+};
+use word_list::WordList;
+
+pub static MIN_BONUS_LEN: usize = 2;
+
+// Builds the letter-pair table used to shuffle a set's letters.  Every pair of
+// letters gets a little padding so that any ordering is possible.
+pub fn letter_table(set: &[String]) -> FreqTable<char> {
+  use std::collections::btree_map::Entry::*;
+
+  let mut table = markov::analyze_corpus(
+    set
+      .iter()
+      .map(|s| ((s.chars().count() as f64).powf(3.4), s.chars())),
+  );
+  let chars: HashSet<_> = set.iter().flat_map(|s| s.chars()).collect();
+
+  let pad = table
+    .values()
+    .flat_map(|t| t.values())
+    .fold(0.0, |s, c| s + c) / 100.0;
+
+  for chr in &chars {
+    let tos = match table.entry(*chr) {
+      Vacant(v) => v.insert(BTreeMap::new()),
+      Occupied(o) => o.into_mut(),
+    };
+
+    for chr in &chars {
+      match tos.entry(*chr) {
+        Vacant(v) => {
+          v.insert(pad);
+        }
+        Occupied(o) => {
+          let o = o.into_mut();
+          *o = *o + pad;
+        }
+      }
+    }
+  }
+
+  table
+}
+
+// Picks a random key of the given length, or None if there aren't any
+fn random_key<R: Rng>(
+  words: &WordList,
+  len: usize,
+  difficulty: Difficulty,
+  rng: &mut R,
+) -> Option<String> {
+  let mut keys = words.get_set_keys(&len, difficulty);
+
+  if keys.is_empty() {
+    return None;
+  }
+
+  let i = rng.gen_range(0, keys.len());
+
+  Some(keys.remove(i))
+}
+
+// Leaves out the words too rare for the difficulty (which makes them bonus
+// words instead), unless that would leave nothing to find.  Words without a
+// commonness score are always kept.
+fn required_words(
+  words: &WordList,
+  set: Vec<String>,
+  difficulty: Difficulty,
+) -> Vec<String> {
+  let min = match difficulty.min_commonness() {
+    Some(m) => m,
+    None => return set,
+  };
+
+  let commonness = words.get_commonness(&set);
+
+  let ret: Vec<String> = set
+    .iter()
+    .filter(|w| commonness.get(*w).map_or(true, |c| *c >= min))
+    .cloned()
+    .collect();
+
+  if ret.is_empty() {
+    set
+  } else {
+    ret
+  }
+}
+
+// Every dictionary word that can be spelled with the given letters
+fn spellable(words: &WordList, count: &CharCounts) -> Vec<String> {
+  words
+    .all_words()
+    .into_iter()
+    .filter(|w| is_subseq(&count_chars(w), count))
+    .collect()
+}
+
+// Every word a set could contain, grouped by length, and every other word that
+// would count as a bonus word.  Each word is paired with whether it was found.
+#[derive(Default)]
+pub struct Analysis {
+  pub by_len: BTreeMap<usize, Vec<(String, bool)>>,
+  pub bonus: Vec<(String, bool)>,
+}
+
+impl Analysis {
+  pub fn lines(&self, show_bonus: bool, width: usize) -> Vec<String> {
+    let mut ret = Vec::new();
+
+    for (len, words) in self.by_len.iter().rev() {
+      push_wrapped(&mut ret, &format!("{} letters:", len), words, width);
+    }
+
+    if show_bonus {
+      ret.push(String::new());
+
+      if self.bonus.is_empty() {
+        ret.push("no bonus words".into());
+      } else {
+        push_wrapped(&mut ret, "bonus:", &self.bonus, width);
+      }
+    }
+
+    ret
+  }
+}
+
+// Lists words after a title, marking the found ones and starting a new
+// (indented) line whenever one gets too long
+fn push_wrapped(
+  lines: &mut Vec<String>,
+  title: &str,
+  words: &[(String, bool)],
+  width: usize,
+) {
+  let mut line = title.to_string();
+
+  for &(ref word, found) in words {
+    let word = if found {
+      format!("*{}", word)
+    } else {
+      format!(" {}", word)
+    };
+
+    // Every line gets at least one word, however narrow the space
+    if line.len() > title.len() && line.len() + 1 + word.len() > width {
+      lines.push(line);
+      line = " ".repeat(title.len());
+    }
+
+    line.push(' ');
+    line.push_str(&word);
+  }
+
+  lines.push(line);
+}
+
+// Finds every word in the set for the given letters, using the same rules as
+// gen-words
+pub fn solve(words: &WordList, letters: &str) -> Analysis {
+  let count = count_chars(letters);
+  let len = letters.chars().count();
+  let mut ret = Analysis::default();
+
+  for word in spellable(words, &count) {
+    let word_len = word.chars().count();
+
+    if in_set(word_len, &count_chars(&word), len, &count) {
+      ret
+        .by_len
+        .entry(word_len)
+        .or_insert_with(Vec::new)
+        .push((word, false));
+    } else if word_len >= MIN_BONUS_LEN {
+      ret.bonus.push((word, false));
+    }
+  }
+
+  ret
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GuessOutcome {
+  Found(u32), // Points awarded
+  AlreadyFound,
+  Bonus(u32),
+  AlreadyBonus,
+  Wrong,
+}
+
+impl GuessOutcome {
+  // The name and points reported to headless and network clients
+  pub fn result(&self) -> (&'static str, u32) {
+    match *self {
+      GuessOutcome::Found(p) => ("found", p),
+      GuessOutcome::AlreadyFound => ("already_found", 0),
+      GuessOutcome::Bonus(p) => ("bonus", p),
+      GuessOutcome::AlreadyBonus => ("already_bonus", 0),
+      GuessOutcome::Wrong => ("wrong", 0),
+    }
+  }
+}
+
+// How far a round had got when it was put aside
+pub struct Progress {
+  pub found: BTreeSet<String>,
+  pub hints: BTreeMap<String, usize>, // Letters revealed, by word
+  pub bonus: Vec<String>,
+  pub score: RoundScore,
+}
+
+pub struct Round<'a> {
+  words: &'a WordList,
+  seed: u64,
+  difficulty: Difficulty,
+  key: String,
+  set: Vec<String>,
+  found: BTreeSet<String>,
+  hints: BTreeMap<String, usize>, // Letters revealed, by word
+  bonus: Vec<String>,
+  letters: String,
+  markov: Markov<char>,
+  rng: GameRng,
+  score: RoundScore,
+  forfeit: bool,
+  timed_out: bool,
+}
+
+impl<'a> Round<'a> {
+  // The rng should be the one seeded from seed; it's passed in so that it can
+  // be used to pick the key first
+  fn new(
+    words: &'a WordList,
+    seed: u64,
+    difficulty: Difficulty,
+    key: String,
+    rng: GameRng,
+  ) -> Self {
+    let set = required_words(words, words.get_set(&key), difficulty);
+    let markov = Markov::new(letter_table(&set));
+    let score = RoundScore::new(key.chars().count(), set.len());
+
+    Self {
+      words,
+      seed,
+      difficulty,
+      letters: key.clone(),
+      key,
+      set,
+      found: BTreeSet::new(),
+      hints: BTreeMap::new(),
+      bonus: Vec::new(),
+      markov,
+      rng,
+      score,
+      forfeit: false,
+      timed_out: false,
+    }
+  }
+
+  // Starts a round with a random key of the given length, or returns None if
+  // there are no keys of that length
+  pub fn random(
+    words: &'a WordList,
+    len: usize,
+    seed: u64,
+    difficulty: Difficulty,
+  ) -> Option<Self> {
+    let mut rng = seed::rng_from_seed(seed);
+
+    random_key(words, len, difficulty, &mut rng)
+      .map(|k| Self::new(words, seed, difficulty, k, rng))
+  }
+
+  // Picks up a round where it left off.  Words that have since gone missing
+  // from the word database are ignored.
+  pub fn resume(
+    words: &'a WordList,
+    seed: u64,
+    difficulty: Difficulty,
+    key: String,
+    progress: Progress,
+  ) -> Self {
+    let rng = seed::rng_from_seed(seed);
+    let mut ret = Self::new(words, seed, difficulty, key, rng);
+
+    for word in progress.found {
+      if ret.set.contains(&word) {
+        ret.found.insert(word);
+      }
+    }
+
+    for (word, count) in progress.hints {
+      if ret.set.contains(&word) {
+        ret.hints.insert(word, count);
+      }
+    }
+
+    ret.bonus = progress.bonus;
+    ret.score = progress.score;
+
+    ret
+  }
+
+  pub fn seed(&self) -> u64 {
+    self.seed
+  }
+
+  pub fn difficulty(&self) -> Difficulty {
+    self.difficulty
+  }
+
+  pub fn key(&self) -> &str {
+    &self.key
+  }
+
+  // In letters rather than bytes, which is what a round's length means
+  pub fn key_len(&self) -> usize {
+    self.key.chars().count()
+  }
+
+  pub fn set(&self) -> &[String] {
+    &self.set
+  }
+
+  // The key's letters, in their current shuffled order
+  pub fn letters(&self) -> &str {
+    &self.letters
+  }
+
+  pub fn score(&self) -> &RoundScore {
+    &self.score
+  }
+
+  pub fn is_found(&self, word: &str) -> bool {
+    self.found.contains(word)
+  }
+
+  pub fn found(&self) -> &BTreeSet<String> {
+    &self.found
+  }
+
+  pub fn hints(&self) -> &BTreeMap<String, usize> {
+    &self.hints
+  }
+
+  pub fn bonus_words(&self) -> &[String] {
+    &self.bonus
+  }
+
+  pub fn remaining(&self) -> Vec<&String> {
+    self.set.iter().filter(|w| !self.found.contains(*w)).collect()
+  }
+
+  pub fn forfeited(&self) -> bool {
+    self.forfeit
+  }
+
+  pub fn timed_out(&self) -> bool {
+    self.timed_out
+  }
+
+  pub fn is_over(&self) -> bool {
+    self.forfeit || self.timed_out || self.score.cleared()
+  }
+
+  pub fn guess(&mut self, word: &str) -> GuessOutcome {
+    if self.is_over() {
+      return GuessOutcome::Wrong;
+    }
+
+    if self.set.iter().any(|w| w == word) {
+      if !self.found.insert(word.to_string()) {
+        return GuessOutcome::AlreadyFound;
+      }
+
+      return GuessOutcome::Found(self.score.add_word(word.chars().count()));
+    }
+
+    if self.bonus.iter().any(|w| w == word) {
+      return GuessOutcome::AlreadyBonus;
+    }
+
+    let len = word.chars().count();
+
+    if len >= MIN_BONUS_LEN
+      && is_subseq(&count_chars(word), &count_chars(&self.key))
+      && self.words.is_word(word)
+    {
+      self.bonus.push(word.to_string());
+
+      return GuessOutcome::Bonus(self.score.add_bonus_word(len));
+    }
+
+    GuessOutcome::Wrong
+  }
+
+  // Shuffles any of the key's letters, such as the ones not yet typed
+  pub fn shuffle_letters(&mut self, letters: &str) -> String {
+    markov::shuffle_str(&self.markov, letters, &mut self.rng)
+  }
+
+  pub fn shuffle(&mut self) {
+    let letters = self.letters.clone();
+
+    self.letters = self.shuffle_letters(&letters);
+  }
+
+  // Lays out the whole set and every possible bonus word, marking the ones
+  // found this round
+  pub fn analyze(&self) -> Analysis {
+    let mut ret = Analysis::default();
+
+    for word in &self.set {
+      ret
+        .by_len
+        .entry(word.chars().count())
+        .or_insert_with(Vec::new)
+        .push((word.clone(), self.found.contains(word)));
+    }
+
+    for words in ret.by_len.values_mut() {
+      words.sort();
+    }
+
+    for word in spellable(self.words, &count_chars(&self.key)) {
+      if word.chars().count() >= MIN_BONUS_LEN && !self.set.contains(&word) {
+        let found = self.bonus.contains(&word);
+
+        ret.bonus.push((word, found));
+      }
+    }
+
+    ret
+  }
+
+  // Reveals another letter of an unsolved word, preferring words that start
+  // with what's been typed so far.  Returns the word and how many of its
+  // letters are now revealed, or None if there was nothing to hint.
+  pub fn hint(&mut self, typed: &str) -> Option<(String, usize)> {
+    if self.is_over() {
+      return None;
+    }
+
+    // The last letter is never given away
+    let mut candidates: Vec<&String> = self
+      .set
+      .iter()
+      .filter(|w| !self.found.contains(*w))
+      .filter(|w| self.hints.get(*w).map_or(0, |h| *h) + 1 < w.chars().count())
+      .collect();
+
+    candidates.sort(); // So that a seeded round hints the same words
+
+    let prefixed: Vec<&String> = candidates
+      .iter()
+      .map(|w| *w)
+      .filter(|w| w.starts_with(typed))
+      .collect();
+
+    let (candidates, at_least) = if typed.is_empty() || prefixed.is_empty() {
+      (candidates, 0)
+    } else {
+      (prefixed, typed.chars().count())
+    };
+
+    if candidates.is_empty() {
+      return None;
+    }
+
+    let word = candidates[self.rng.gen_range(0, candidates.len())].clone();
+    let hinted = self.hints.get(&word).map_or(0, |h| *h);
+    let len = word.chars().count();
+    let count = cmp::min(cmp::max(hinted, at_least) + 1, len - 1);
+
+    self.hints.insert(word.clone(), count);
+    self.score.add_hint();
+
+    Some((word, count))
+  }
+
+  // Awards the time bonus if a timed round was cleared with time to spare
+  pub fn finish_early(&mut self, left: Duration) -> u32 {
+    if !self.score.cleared() {
+      return 0;
+    }
+
+    self.score.add_time_bonus(left)
+  }
+
+  pub fn forfeit(&mut self) {
+    self.forfeit = true;
+  }
+
+  pub fn time_out(&mut self) {
+    self.timed_out = true;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use score::{word_points, HINT_PENALTY};
+
+  fn words() -> WordList {
+    WordList::with_set(
+      "tones",
+      &["notes", "onset", "stone", "tones", "one", "ten"],
+      &["ton", "net", "zebra"],
+    )
+  }
+
+  fn round(words: &WordList) -> Round {
+    Round::random(words, 5, 0, Difficulty::Hard).unwrap()
+  }
+
+  #[test]
+  fn picks_set() {
+    let words = words();
+    let round = round(&words);
+
+    assert_eq!(round.key(), "tones");
+    assert_eq!(round.set().len(), 6);
+    assert_eq!(round.score().total(), 6);
+    assert!(Round::random(&words, 4, 0, Difficulty::Hard).is_none());
+  }
+
+  #[test]
+  fn multi_byte_key() {
+    let words = WordList::with_set("éte", &["éte", "été"], &[]);
+    let round = Round::random(&words, 3, 0, Difficulty::Hard).unwrap();
+
+    assert_eq!(round.key(), "éte");
+    assert_eq!(round.key_len(), 3);
+  }
+
+  #[test]
+  fn no_sets_under_cap() {
+    let words = words();
+
+    // Unrated sets are offered at any difficulty
+    assert!(Round::random(&words, 5, 0, Difficulty::Easy).is_some());
+
+    words.rate_set("tones", 90);
+
+    assert!(words.get_set_keys(&5, Difficulty::Easy).is_empty());
+    assert!(Round::random(&words, 5, 0, Difficulty::Easy).is_none());
+    assert!(Round::random(&words, 5, 0, Difficulty::Hard).is_some());
+  }
+
+  #[test]
+  fn guess_found() {
+    let words = words();
+    let mut round = round(&words);
+
+    assert_eq!(round.guess("one"), GuessOutcome::Found(word_points(3)));
+    assert_eq!(round.guess("ten"), GuessOutcome::Found(word_points(3)));
+    assert!(round.is_found("one"));
+    assert_eq!(round.score().found(), 2);
+    assert_eq!(round.score().points(), word_points(3) * 2);
+  }
+
+  #[test]
+  fn guess_already_found() {
+    let words = words();
+    let mut round = round(&words);
+
+    round.guess("stone");
+
+    let points = round.score().points();
+
+    assert_eq!(round.guess("stone"), GuessOutcome::AlreadyFound);
+    assert_eq!(round.score().found(), 1);
+    assert_eq!(round.score().points(), points);
+  }
+
+  #[test]
+  fn guess_bonus() {
+    let words = words();
+    let mut round = round(&words);
+
+    assert_eq!(round.guess("ton"), GuessOutcome::Bonus(word_points(3) / 2));
+    assert_eq!(round.guess("ton"), GuessOutcome::AlreadyBonus);
+    assert_eq!(round.bonus_words(), ["ton"]);
+    assert_eq!(round.score().bonus(), 1);
+    assert_eq!(round.score().found(), 0);
+  }
+
+  #[test]
+  fn guess_wrong() {
+    let words = words();
+    let mut round = round(&words);
+
+    // Not a word, not spelled from the key, too short and empty
+    for word in &["tset", "zebra", "t", ""] {
+      assert_eq!(round.guess(word), GuessOutcome::Wrong);
+    }
+
+    assert_eq!(round.score().points(), 0);
+    assert!(round.bonus_words().is_empty());
+  }
+
+  #[test]
+  fn remaining() {
+    let words = words();
+    let mut round = round(&words);
+
+    assert_eq!(round.remaining().len(), 6);
+
+    round.guess("stone");
+    round.guess("ton");
+
+    let remaining = round.remaining();
+
+    assert_eq!(remaining.len(), 5);
+    assert!(!remaining.contains(&&"stone".to_string()));
+  }
+
+  // A hint taken before anything is found still costs points once some come in
+  #[test]
+  fn hint_at_zero_points() {
+    let words = words();
+    let mut round = round(&words);
+
+    assert!(round.hint("").is_some());
+    assert_eq!(round.score().points(), 0);
+
+    round.guess("one");
+
+    assert_eq!(round.score().hints(), 1);
+    assert_eq!(round.score().points(), word_points(3) - HINT_PENALTY);
+  }
+
+  #[test]
+  fn clear_set() {
+    let words = words();
+    let mut round = round(&words);
+    let set = round.set().to_vec();
+
+    for word in &set {
+      assert!(!round.is_over());
+      round.guess(word);
+    }
+
+    assert!(round.is_over());
+    assert!(!round.forfeited());
+    assert!(round.remaining().is_empty());
+  }
+
+  #[test]
+  fn forfeit() {
+    let words = words();
+    let mut round = round(&words);
+
+    round.guess("stone");
+    round.forfeit();
+
+    assert!(round.is_over());
+    assert!(round.forfeited());
+    assert_eq!(round.guess("notes"), GuessOutcome::Wrong);
+    assert!(round.hint("").is_none());
+    assert_eq!(round.remaining().len(), 5);
+  }
+
+  #[test]
+  fn resume() {
+    let words = words();
+    let mut played = round(&words);
+
+    played.guess("stone");
+    played.guess("ton");
+
+    let round = Round::resume(
+      &words,
+      played.seed(),
+      played.difficulty(),
+      played.key().into(),
+      Progress {
+        found: vec!["stone".to_string(), "gone".to_string()]
+          .into_iter()
+          .collect(),
+        hints: BTreeMap::new(),
+        bonus: played.bonus_words().to_vec(),
+        score: played.score().clone(),
+      },
+    );
+
+    // Words no longer in the set are dropped
+    assert_eq!(round.found().len(), 1);
+    assert!(round.is_found("stone"));
+    assert_eq!(round.bonus_words(), ["ton"]);
+    assert_eq!(round.score().points(), played.score().points());
+  }
+}
@@ -0,0 +1,216 @@
+// A line-based frontend for bots, scripts and other frontends.  Each line of
+// input is a JSON command, and each command gets exactly one line of JSON in
+// response.  See the README for the list of commands.
+
+use difficulty::Difficulty;
+use game::Round;
+use rand::Rng;
+use seed::GameRng;
+use serde_json;
+use std::io::{self, prelude::*};
+use word_list::WordList;
+use Result;
+
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum Command {
+  NewRound {
+    #[serde(default)]
+    len: Option<usize>,
+    #[serde(default)]
+    difficulty: Option<String>,
+  },
+  Guess {
+    word: String,
+  },
+  Shuffle,
+  Forfeit,
+  State,
+  Quit,
+}
+
+#[derive(Serialize)]
+struct State {
+  len: usize,
+  difficulty: &'static str,
+  letters: String,
+  points: u32,
+  found: Vec<String>,
+  remaining: Vec<usize>, // Lengths of the words left to find
+  bonus: Vec<String>,
+  hints: usize,
+  over: bool,
+  forfeited: bool,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  key: Option<String>, // Only given once the round is over
+  #[serde(skip_serializing_if = "Option::is_none")]
+  missed: Option<Vec<String>>,
+}
+
+impl State {
+  fn new(round: &Round) -> Self {
+    let over = round.is_over();
+    let score = round.score();
+
+    Self {
+      len: round.key().chars().count(),
+      difficulty: round.difficulty().name(),
+      letters: round.letters().into(),
+      points: score.points(),
+      found: round
+        .set()
+        .iter()
+        .filter(|w| round.is_found(w))
+        .cloned()
+        .collect(),
+      remaining: round
+        .remaining()
+        .iter()
+        .map(|w| w.chars().count())
+        .collect(),
+      bonus: round.bonus_words().to_vec(),
+      hints: score.hints(),
+      over,
+      forfeited: round.forfeited(),
+      key: if over { Some(round.key().into()) } else { None },
+      missed: if over {
+        Some(round.remaining().into_iter().cloned().collect())
+      } else {
+        None
+      },
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Response {
+  State {
+    state: State,
+  },
+  Guess {
+    word: String,
+    result: &'static str,
+    points: u32,
+    state: State,
+  },
+  Error {
+    message: String,
+  },
+}
+
+fn error(message: &str) -> Response {
+  Response::Error {
+    message: message.into(),
+  }
+}
+
+// lens is the range of lengths to pick from when a new round doesn't give one,
+// and difficulty likewise
+pub fn run(
+  words: &WordList,
+  rng: &mut GameRng,
+  lens: Option<&Vec<usize>>,
+  difficulty: Difficulty,
+) -> Result<()> {
+  let stdin = io::stdin();
+  let stdout = io::stdout();
+  let mut out = stdout.lock();
+
+  let mut round: Option<Round> = None;
+
+  for line in stdin.lock().lines() {
+    let line = line?;
+
+    if line.trim().is_empty() {
+      continue;
+    }
+
+    let cmd: Command = match serde_json::from_str(&line) {
+      Ok(c) => c,
+      Err(e) => {
+        let resp = error(&format!("invalid command: {}", e));
+
+        writeln!(out, "{}", serde_json::to_string(&resp).unwrap())?;
+        out.flush()?;
+        continue;
+      }
+    };
+
+    let resp = match cmd {
+      Command::NewRound { len, difficulty: d } => {
+        let len = match (len, lens) {
+          (Some(l), _) => Some(l),
+          (None, Some(lens)) => Some(lens[rng.gen_range(0, lens.len())]),
+          (None, None) => None,
+        };
+
+        let d = match d {
+          Some(d) => d.parse::<Difficulty>(),
+          None => Ok(difficulty),
+        };
+
+        match (len, d) {
+          (_, Err(e)) => error(&e),
+          (None, _) => error("no word length given"),
+          (Some(len), Ok(d)) => match Round::random(words, len, rng.gen(), d)
+          {
+            Some(mut r) => {
+              r.shuffle(); // The letters start out as the key itself
+
+              let state = State::new(&r);
+
+              round = Some(r);
+              Response::State { state }
+            }
+            None => error(&format!("no words found of length {}", len)),
+          },
+        }
+      }
+      Command::Quit => break,
+      cmd => match round {
+        None => error("no round in progress"),
+        Some(ref mut round) => match cmd {
+          Command::Guess { word } => {
+            if round.is_over() {
+              error("the round is over")
+            } else {
+              let word = word.trim().to_lowercase();
+              let (result, points) = round.guess(&word).result();
+
+              Response::Guess {
+                word,
+                result,
+                points,
+                state: State::new(round),
+              }
+            }
+          }
+          Command::Shuffle => {
+            round.shuffle();
+
+            Response::State {
+              state: State::new(round),
+            }
+          }
+          Command::Forfeit => {
+            round.forfeit();
+
+            Response::State {
+              state: State::new(round),
+            }
+          }
+          Command::State => Response::State {
+            state: State::new(round),
+          },
+          Command::NewRound { .. } | Command::Quit => unreachable!(),
+        },
+      },
+    };
+
+    writeln!(out, "{}", serde_json::to_string(&resp).unwrap())?;
+    out.flush()?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,75 @@
+// A list of previously-submitted guesses that can be stepped through, similar
+// to a shell's command history
+pub struct History {
+  entries: Vec<String>,
+  pos: Option<usize>, // None if not currently recalling an entry
+  draft: String,      // Whatever was typed before recalling started
+}
+
+impl History {
+  pub fn new() -> Self {
+    Self {
+      entries: Vec::new(),
+      pos: None,
+      draft: String::new(),
+    }
+  }
+
+  pub fn from_entries(entries: Vec<String>) -> Self {
+    Self {
+      entries,
+      pos: None,
+      draft: String::new(),
+    }
+  }
+
+  pub fn entries(&self) -> &[String] {
+    &self.entries
+  }
+
+  pub fn push(&mut self, entry: &str) {
+    self.pos = None;
+
+    if entry.is_empty() || self.entries.last().map_or(false, |e| e == entry) {
+      return;
+    }
+
+    self.entries.push(entry.to_string());
+  }
+
+  // Returns the entry before the current one, saving current as a draft if
+  // recalling just started
+  pub fn prev(&mut self, current: &str) -> Option<&str> {
+    let pos = match self.pos {
+      Some(0) => return None,
+      Some(p) => p - 1,
+      None => {
+        if self.entries.is_empty() {
+          return None;
+        }
+
+        self.draft = current.to_string();
+        self.entries.len() - 1
+      }
+    };
+
+    self.pos = Some(pos);
+    Some(&self.entries[pos])
+  }
+
+  // Returns the entry after the current one, or the saved draft when stepping
+  // past the newest entry
+  pub fn next(&mut self) -> Option<&str> {
+    match self.pos {
+      None => None,
+      Some(p) if p + 1 < self.entries.len() => {
+        self.pos = Some(p + 1);
+        Some(&self.entries[p + 1])
+      }
+      Some(_) => {
+        self.pos = None;
+        Some(&self.draft)
+      }
+    }
+  }
+}
@@ -0,0 +1,33 @@
+// Helpers for hot-seat rounds, where players take turns at the one keyboard
+
+use players::Players;
+use tui::{controls::*, element as el};
+
+// The color of whoever found the word
+pub fn finder_pair(
+  players: Option<&Players>,
+  player_pairs: &[i32],
+  word: &str,
+) -> Option<i32> {
+  players
+    .and_then(|p| p.finder(word))
+    .map(|i| player_pairs[i])
+}
+
+pub fn show_players(
+  scoreboard: Option<&el::ElemWrapper<Scoreboard>>,
+  players: Option<&Players>,
+) {
+  if let (Some(s), Some(p)) = (scoreboard, players) {
+    let mut s = s.borrow_mut();
+
+    s.set_players(
+      p.list()
+        .iter()
+        .map(|p| (p.name.clone(), p.score(), p.words.len()))
+        .collect(),
+    );
+
+    s.set_turn(p.turn());
+  }
+}
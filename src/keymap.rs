@@ -0,0 +1,517 @@
+// Key bindings.  Keys are written the way a player would name them ("tab",
+// "ctrl+d", "shift+left"), and are only turned into the input ncurses gives for
+// them once the terminal is up, since some of them (like ctrl+left) differ
+// between terminals.
+
+use nc;
+use serde::de::{self, Deserialize, Deserializer};
+use std::{
+  char,
+  collections::{BTreeMap, HashMap},
+  ffi::{CStr, CString},
+  fmt,
+  os::raw::{c_char, c_int},
+  result::Result as StdResult,
+  str::FromStr,
+};
+
+// ncurses::tigetstr() assumes every capability exists, and crashes on the ones
+// a terminal doesn't have, so these are declared here instead
+extern "C" {
+  fn tigetstr(capname: *const c_char) -> *const c_char;
+  fn key_defined(definition: *const c_char) -> c_int;
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+  Submit,
+  Shuffle,
+  Clear,
+  Forfeit,
+  ToggleSort,
+  Hint,
+  PrevWord,
+  NextWord,
+  Undo,
+  HistoryPrev,
+  HistoryNext,
+  Left,
+  Right,
+  Home,
+  End,
+  DelLeft,
+  DelRight,
+  Stats,
+  Analysis,
+  ToggleBonus,
+  PageUp,
+  PageDown,
+  FocusNext,
+  FocusPrev,
+  Quit,
+}
+
+// (action, name, description), in the order bindings are listed
+static ACTIONS: &[(Action, &str, &str)] = &[
+  (Action::Submit, "submit", "submit your guess"),
+  (Action::Shuffle, "shuffle", "shuffle the remaining letters"),
+  (Action::Clear, "clear", "clear the word box"),
+  (
+    Action::Forfeit,
+    "forfeit",
+    "forfeit the round (online, vote to give up)",
+  ),
+  (
+    Action::ToggleSort,
+    "toggle_sort",
+    "toggle sorting the remaining letters",
+  ),
+  (Action::Hint, "hint", "reveal a letter of an unsolved word"),
+  (
+    Action::PrevWord,
+    "prev_word",
+    "select the previous found word",
+  ),
+  (Action::NextWord, "next_word", "select the next found word"),
+  (Action::Undo, "undo", "take back a step of a word ladder"),
+  (
+    Action::HistoryPrev,
+    "history_prev",
+    "step back through your guesses",
+  ),
+  (
+    Action::HistoryNext,
+    "history_next",
+    "step forward through your guesses",
+  ),
+  (Action::Left, "left", "move the cursor left"),
+  (Action::Right, "right", "move the cursor right"),
+  (Action::Home, "home", "move the cursor to the start"),
+  (Action::End, "end", "move the cursor to the end"),
+  (
+    Action::DelLeft,
+    "del_left",
+    "delete the letter before the cursor",
+  ),
+  (
+    Action::DelRight,
+    "del_right",
+    "delete the letter after the cursor",
+  ),
+  (Action::Stats, "stats", "show your statistics"),
+  (
+    Action::Analysis,
+    "analysis",
+    "list every word in the set after a round",
+  ),
+  (
+    Action::ToggleBonus,
+    "toggle_bonus",
+    "show bonus words in that list",
+  ),
+  (Action::PageUp, "page_up", "scroll the list of words back"),
+  (Action::PageDown, "page_down", "scroll the list of words on"),
+  (Action::FocusNext, "focus_next", "move to the next control"),
+  (Action::FocusPrev, "focus_prev", "move to the previous control"),
+  (Action::Quit, "quit", "quit, saving a round in progress"),
+];
+
+static DEFAULTS: &[(Action, &[&str])] = &[
+  (Action::Submit, &["enter"]),
+  (Action::Shuffle, &["tab"]),
+  (Action::Clear, &["ctrl+backspace", "ctrl+w"]),
+  (Action::Forfeit, &["esc"]),
+  (Action::ToggleSort, &["shift+tab"]),
+  (Action::Hint, &["?"]),
+  (Action::PrevWord, &["["]),
+  (Action::NextWord, &["]"]),
+  (Action::Undo, &["ctrl+u"]),
+  (Action::HistoryPrev, &["up"]),
+  (Action::HistoryNext, &["down"]),
+  (Action::Left, &["left"]),
+  (Action::Right, &["right"]),
+  (Action::Home, &["home", "ctrl+left"]),
+  (Action::End, &["end", "ctrl+right"]),
+  (Action::DelLeft, &["backspace"]),
+  (Action::DelRight, &["delete"]),
+  (Action::Stats, &["f2"]),
+  (Action::Analysis, &["f3"]),
+  (Action::ToggleBonus, &["b"]),
+  (Action::PageUp, &["pageup"]),
+  (Action::PageDown, &["pagedown"]),
+  (Action::FocusNext, &["ctrl+n"]),
+  (Action::FocusPrev, &["ctrl+p"]),
+  (Action::Quit, &["ctrl+d"]),
+];
+
+impl Action {
+  pub fn name(&self) -> &'static str {
+    ACTIONS.iter().find(|a| a.0 == *self).unwrap().1
+  }
+}
+
+impl FromStr for Action {
+  type Err = String;
+
+  fn from_str(s: &str) -> StdResult<Self, String> {
+    match ACTIONS.iter().find(|a| a.1 == s) {
+      Some(a) => Ok(a.0),
+      None => Err(format!("unknown action '{}'", s)),
+    }
+  }
+}
+
+// Modifiers, as xterm encodes them (one more than the sum of these)
+static SHIFT: u8 = 1;
+static ALT: u8 = 2;
+static CTRL: u8 = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Nav {
+  Up,
+  Down,
+  Left,
+  Right,
+  Home,
+  End,
+  Delete,
+  PageUp,
+  PageDown,
+}
+
+// (key, name, key code, terminfo capability for the key with modifiers)
+static NAVS: &[(Nav, &str, i32, &str)] = &[
+  (Nav::Up, "up", nc::KEY_UP, "kUP"),
+  (Nav::Down, "down", nc::KEY_DOWN, "kDN"),
+  (Nav::Left, "left", nc::KEY_LEFT, "kLFT"),
+  (Nav::Right, "right", nc::KEY_RIGHT, "kRIT"),
+  (Nav::Home, "home", nc::KEY_HOME, "kHOM"),
+  (Nav::End, "end", nc::KEY_END, "kEND"),
+  (Nav::Delete, "delete", nc::KEY_DC, "kDC"),
+  (Nav::PageUp, "pageup", nc::KEY_PPAGE, "kPRV"),
+  (Nav::PageDown, "pagedown", nc::KEY_NPAGE, "kNXT"),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key {
+  Char(char),
+  Ctrl(char),
+  Tab,
+  ShiftTab,
+  Enter,
+  Esc,
+  Backspace,
+  CtrlBackspace,
+  Nav(Nav, u8), // With any modifiers, or 0
+  F(u8),
+}
+
+// One read from the keyboard: either a key code for a function key, or a
+// character (which can be a control character, such as tab)
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Input {
+  Code(i32),
+  Char(char),
+  Timeout, // Or any other error
+}
+
+pub fn read_input(win: nc::WINDOW) -> Input {
+  match nc::wget_wch(win) {
+    Some(nc::WchResult::KeyCode(c)) => Input::Code(c),
+    Some(nc::WchResult::Char(c)) => {
+      char::from_u32(c).map_or(Input::Timeout, Input::Char)
+    }
+    None => Input::Timeout,
+  }
+}
+
+fn term_str(cap: &str) -> Option<CString> {
+  let cap = CString::new(cap).unwrap();
+  let s = unsafe { tigetstr(cap.as_ptr()) };
+
+  // Missing capabilities come back as NULL, or as -1 if they aren't strings
+  if s.is_null() || s as isize == -1 {
+    return None;
+  }
+
+  Some(unsafe { CStr::from_ptr(s) }.to_owned())
+}
+
+// Terminals that send DEL for backspace have ncurses report it as
+// KEY_BACKSPACE, while the rest send BS for that instead
+fn byte_input(b: u8, kbs: &Option<CString>) -> Input {
+  match *kbs {
+    Some(ref s) if s.as_bytes() == [b] => Input::Code(nc::KEY_BACKSPACE),
+    _ => Input::Char(b as char),
+  }
+}
+
+impl Key {
+  // The input read_input() gives for this key, if the terminal has it.  Only
+  // valid once ncurses has been started.
+  fn input(&self, kbs: &Option<CString>) -> Option<Input> {
+    let ret = match *self {
+      Key::Char(c) if c.is_ascii() => byte_input(c as u8, kbs),
+      Key::Char(c) => Input::Char(c),
+      Key::Ctrl(c) => byte_input(c.to_ascii_uppercase() as u8 & 0x1F, kbs),
+      Key::Tab => Input::Char('\t'),
+      Key::ShiftTab => Input::Code(nc::KEY_BTAB),
+      Key::Enter => Input::Char('\n'),
+      Key::Esc => Input::Char('\x1B'),
+      Key::Backspace => byte_input(0x7F, kbs),
+      Key::CtrlBackspace => byte_input(0x08, kbs),
+      Key::Nav(nav, 0) => {
+        Input::Code(NAVS.iter().find(|n| n.0 == nav).unwrap().2)
+      }
+      Key::Nav(nav, mods) => {
+        let base = NAVS.iter().find(|n| n.0 == nav).unwrap().3;
+
+        // Shifted keys have their own capabilities, e.g. kLFT, while the rest
+        // are numbered by xterm's modifier code, e.g. kLFT5 for ctrl+left
+        let cap = if mods == SHIFT {
+          base.to_string()
+        } else {
+          format!("{}{}", base, mods + 1)
+        };
+
+        let seq = term_str(&cap)?;
+
+        match unsafe { key_defined(seq.as_ptr()) } {
+          c if c > 0 => Input::Code(c),
+          _ => return None,
+        }
+      }
+      Key::F(n) => Input::Code(nc::KEY_F(n)),
+    };
+
+    Some(ret)
+  }
+}
+
+impl FromStr for Key {
+  type Err = String;
+
+  fn from_str(s: &str) -> StdResult<Self, String> {
+    let err = || format!("unknown key '{}'", s);
+
+    if s.chars().count() == 1 {
+      return match s.chars().next().unwrap() {
+        c if !c.is_control() && !c.is_whitespace() => Ok(Key::Char(c)),
+        _ => Err(err()),
+      };
+    }
+
+    let mut parts: Vec<_> = s.split('+').collect();
+    let name = parts.pop().unwrap().to_lowercase();
+    let mut mods = 0;
+
+    for part in parts {
+      mods |= match &*part.to_lowercase() {
+        "shift" => SHIFT,
+        "alt" | "meta" => ALT,
+        "ctrl" | "control" => CTRL,
+        _ => return Err(err()),
+      };
+    }
+
+    if let Some(nav) = NAVS.iter().find(|n| n.1 == name) {
+      return Ok(Key::Nav(nav.0, mods));
+    }
+
+    let key = match (&*name, mods) {
+      ("tab", 0) => Key::Tab,
+      ("tab", m) if m == SHIFT => Key::ShiftTab,
+      ("enter", 0) | ("return", 0) => Key::Enter,
+      ("esc", 0) | ("escape", 0) => Key::Esc,
+      ("space", 0) => Key::Char(' '),
+      ("backspace", 0) => Key::Backspace,
+      ("backspace", m) if m == CTRL => Key::CtrlBackspace,
+      (n, m) if m == CTRL && n.len() == 1 => match n.chars().next().unwrap() {
+        c @ 'a'..='z'
+        | c @ '@'
+        | c @ '['
+        | c @ '\\'
+        | c @ ']'
+        | c @ '^'
+        | c @ '_' => Key::Ctrl(c),
+        _ => return Err(err()),
+      },
+      (n, 0) if n.starts_with('f') => match n[1..].parse() {
+        Ok(n) if n >= 1 && n <= 12 => Key::F(n),
+        _ => return Err(err()),
+      },
+      _ => return Err(err()),
+    };
+
+    Ok(key)
+  }
+}
+
+impl fmt::Display for Key {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Key::Char(' ') => fmt.write_str("space"),
+      Key::Char(c) => write!(fmt, "{}", c),
+      Key::Ctrl(c) => write!(fmt, "ctrl+{}", c),
+      Key::Tab => fmt.write_str("tab"),
+      Key::ShiftTab => fmt.write_str("shift+tab"),
+      Key::Enter => fmt.write_str("enter"),
+      Key::Esc => fmt.write_str("esc"),
+      Key::Backspace => fmt.write_str("backspace"),
+      Key::CtrlBackspace => fmt.write_str("ctrl+backspace"),
+      Key::Nav(nav, mods) => {
+        for &(m, name) in &[(CTRL, "ctrl+"), (ALT, "alt+"), (SHIFT, "shift+")] {
+          if mods & m != 0 {
+            fmt.write_str(name)?;
+          }
+        }
+
+        fmt.write_str(NAVS.iter().find(|n| n.0 == nav).unwrap().1)
+      }
+      Key::F(n) => write!(fmt, "f{}", n),
+    }
+  }
+}
+
+#[derive(Clone)]
+pub struct Keymap {
+  bindings: HashMap<Action, Vec<Key>>,
+}
+
+impl Keymap {
+  // Binds the keys given to an action in place of its old ones.  A key can
+  // only be bound to one action, so it's taken from any other it was bound to.
+  pub fn set(&mut self, action: Action, keys: Vec<Key>) {
+    for others in self.bindings.values_mut() {
+      others.retain(|k| !keys.contains(k));
+    }
+
+    self.bindings.insert(action, keys);
+  }
+
+  pub fn keys(&self, action: Action) -> &[Key] {
+    self.bindings.get(&action).map_or(&[][..], |k| &k[..])
+  }
+
+  // One line per action, for listing the bindings
+  pub fn lines(&self) -> Vec<String> {
+    ACTIONS
+      .iter()
+      .map(|&(action, name, description)| {
+        let keys: Vec<_> =
+          self.keys(action).iter().map(|k| k.to_string()).collect();
+        let keys = if keys.is_empty() {
+          "(none)".to_string()
+        } else {
+          keys.join(", ")
+        };
+
+        format!("{:<14}{:<24}{}", name, keys, description)
+      })
+      .collect()
+  }
+
+  // Looks up the input for each key on this terminal.  Keys the terminal doesn't have
+  // are left out.
+  pub fn resolve(&self) -> KeyCodes {
+    let kbs = term_str("kbs");
+    let mut inputs = HashMap::new();
+
+    for &(action, _, _) in ACTIONS {
+      for key in self.keys(action) {
+        if let Some(input) = key.input(&kbs) {
+          inputs.insert(input, action);
+        }
+      }
+    }
+
+    KeyCodes {
+      keymap: self.clone(),
+      inputs,
+    }
+  }
+}
+
+impl Default for Keymap {
+  fn default() -> Self {
+    Self {
+      bindings: DEFAULTS
+        .iter()
+        .map(|&(action, keys)| {
+          (action, keys.iter().map(|k| k.parse().unwrap()).collect())
+        })
+        .collect(),
+    }
+  }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+  One(String),
+  Many(Vec<String>),
+}
+
+// Read from the config as a map of actions to keys, which replace the default
+// keys for those actions, e.g. { "shuffle": ["tab", "ctrl+s"] }
+impl<'de> Deserialize<'de> for Keymap {
+  fn deserialize<D>(de: D) -> StdResult<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    let overrides = BTreeMap::<String, KeyList>::deserialize(de)?;
+    let mut ret = Self::default();
+
+    for (action, keys) in overrides {
+      let action = action.parse().map_err(de::Error::custom)?;
+      let keys = match keys {
+        KeyList::One(k) => vec![k],
+        KeyList::Many(k) => k,
+      };
+      let keys = keys
+        .iter()
+        .map(|k| k.parse())
+        .collect::<StdResult<_, _>>()
+        .map_err(de::Error::custom)?;
+
+      ret.set(action, keys);
+    }
+
+    Ok(ret)
+  }
+}
+
+// A keymap resolved for the current terminal
+pub struct KeyCodes {
+  keymap: Keymap,
+  inputs: HashMap<Input, Action>,
+}
+
+impl KeyCodes {
+  pub fn action(&self, input: Input) -> Option<Action> {
+    self.inputs.get(&input).cloned()
+  }
+
+  // The same keys, less the ones bound to actions not listed.  A screen that
+  // doesn't use an action then sees its keys as plain keys, so a letter bound
+  // to it can still be typed.
+  pub fn only(&self, actions: &[Action]) -> Self {
+    Self {
+      keymap: self.keymap.clone(),
+      inputs: self
+        .inputs
+        .iter()
+        .filter(|&(_, a)| actions.contains(a))
+        .map(|(i, a)| (*i, *a))
+        .collect(),
+    }
+  }
+
+  // The first key bound to an action, for telling the player what to press
+  pub fn name(&self, action: Action) -> String {
+    match self.keymap.keys(action).first() {
+      Some(k) => k.to_string(),
+      None => format!("({} is unbound)", action.name()),
+    }
+  }
+}
@@ -0,0 +1,251 @@
+// The rules of a word ladder, independent of any frontend.  The player turns
+// the start word into the target word one step at a time, where each step
+// changes one letter (and may rearrange the rest), and every word along the way
+// has to be in the dictionary.
+
+use char_counts::*;
+use rand::Rng;
+use seed;
+use std::collections::{HashMap, VecDeque};
+use word_list::WordList;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StepOutcome {
+  Step,
+  Solved,
+  Repeated, // Already part of the ladder
+  NotAStep,
+  NotAWord,
+}
+
+fn sorted(word: &str) -> String {
+  let mut chars: Vec<char> = word.chars().collect();
+  chars.sort();
+  chars.into_iter().collect()
+}
+
+pub struct Ladder<'a> {
+  words: &'a WordList,
+  start: String,
+  target: String,
+  min_steps: usize,
+  chain: Vec<String>, // Every word so far, starting with the start word
+  anagrams: HashMap<String, Vec<String>>, // Dictionary words, by sorted letters
+  letters: Vec<char>, // Every letter used by those words
+  forfeit: bool,
+}
+
+impl<'a> Ladder<'a> {
+  fn new(
+    words: &'a WordList,
+    dictionary: &[String],
+    start: String,
+    target: String,
+    min_steps: usize,
+  ) -> Self {
+    let mut anagrams: HashMap<String, Vec<String>> = HashMap::new();
+
+    for word in dictionary {
+      if word.chars().count() == start.chars().count() {
+        anagrams
+          .entry(sorted(word))
+          .or_insert_with(Vec::new)
+          .push(word.clone());
+      }
+    }
+
+    let mut letters: Vec<char> =
+      anagrams.keys().flat_map(|k| k.chars()).collect();
+    letters.sort();
+    letters.dedup();
+
+    Self {
+      words,
+      chain: vec![start.clone()],
+      start,
+      target,
+      min_steps,
+      anagrams,
+      letters,
+      forfeit: false,
+    }
+  }
+
+  // Picks a random ladder of the given length, or returns None if there
+  // aren't any.  The dictionary is every word in the word list, which is slow
+  // enough to load that it's best loaded once and shared between ladders.
+  pub fn random(
+    words: &'a WordList,
+    dictionary: &[String],
+    len: usize,
+    seed: u64,
+  ) -> Option<Self> {
+    let mut ladders = words.get_ladders(&len);
+
+    if ladders.is_empty() {
+      return None;
+    }
+
+    let mut rng = seed::rng_from_seed(seed);
+    let (start, target, steps) =
+      ladders.remove(rng.gen_range(0, ladders.len()));
+
+    Some(Self::new(words, dictionary, start, target, steps))
+  }
+
+  pub fn start(&self) -> &str {
+    &self.start
+  }
+
+  pub fn target(&self) -> &str {
+    &self.target
+  }
+
+  // The fewest steps the ladder can be solved in
+  pub fn min_steps(&self) -> usize {
+    self.min_steps
+  }
+
+  pub fn chain(&self) -> &[String] {
+    &self.chain
+  }
+
+  pub fn current(&self) -> &str {
+    self.chain.last().unwrap()
+  }
+
+  pub fn steps(&self) -> usize {
+    self.chain.len() - 1
+  }
+
+  pub fn solved(&self) -> bool {
+    self.current() == self.target
+  }
+
+  pub fn forfeited(&self) -> bool {
+    self.forfeit
+  }
+
+  pub fn is_over(&self) -> bool {
+    self.forfeit || self.solved()
+  }
+
+  pub fn guess(&mut self, word: &str) -> StepOutcome {
+    if self.is_over() {
+      return StepOutcome::NotAStep;
+    }
+
+    if self.chain.iter().any(|w| w == word) {
+      return StepOutcome::Repeated;
+    }
+
+    if !is_ladder_step(&count_chars(self.current()), &count_chars(word)) {
+      return StepOutcome::NotAStep;
+    }
+
+    if !self.words.is_word(word) {
+      return StepOutcome::NotAWord;
+    }
+
+    self.chain.push(word.to_string());
+
+    if self.solved() {
+      StepOutcome::Solved
+    } else {
+      StepOutcome::Step
+    }
+  }
+
+  // Takes back the last step, returning whether there was one
+  pub fn undo(&mut self) -> bool {
+    if self.is_over() || self.chain.len() < 2 {
+      return false;
+    }
+
+    self.chain.pop();
+    true
+  }
+
+  pub fn forfeit(&mut self) {
+    self.forfeit = true;
+  }
+
+  // Finds the shortest way from the current word to the target, not counting
+  // the current word itself.  Returns None if the target can't be reached.
+  pub fn solution(&self) -> Option<Vec<String>> {
+    let from = sorted(self.current());
+    let to = sorted(&self.target);
+
+    let mut prev: HashMap<String, String> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    queue.push_back(from.clone());
+
+    // The target is only ever reached as a neighbor, since being on one of its
+    // anagrams still takes two steps: one away and one back
+    'search: while let Some(letters) = queue.pop_front() {
+      for next in self.neighbors(&letters) {
+        if prev.contains_key(&next) || (next == from && next != to) {
+          continue;
+        }
+
+        prev.insert(next.clone(), letters.clone());
+
+        if next == to {
+          break 'search;
+        }
+
+        queue.push_back(next);
+      }
+    }
+
+    if !prev.contains_key(&to) {
+      return None;
+    }
+
+    let mut ret = vec![self.target.clone()];
+    let mut letters = &to;
+
+    loop {
+      let p = &prev[letters];
+
+      if *p == from {
+        break;
+      }
+
+      ret.push(self.anagrams[p][0].clone());
+      letters = p;
+    }
+
+    ret.reverse();
+
+    Some(ret)
+  }
+
+  // The sorted letters of every word one step away
+  fn neighbors(&self, letters: &str) -> Vec<String> {
+    let chars: Vec<char> = letters.chars().collect();
+    let mut ret = Vec::new();
+
+    for (i, from) in chars.iter().enumerate() {
+      for to in &self.letters {
+        if to == from {
+          continue;
+        }
+
+        let mut next = chars.clone();
+        next[i] = *to;
+
+        let next = sorted(&next.into_iter().collect::<String>());
+
+        if self.anagrams.contains_key(&next) && !ret.contains(&next) {
+          ret.push(next);
+        }
+      }
+    }
+
+    ret.sort(); // So that the same solution is always given
+
+    ret
+  }
+}
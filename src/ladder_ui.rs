@@ -0,0 +1,309 @@
+// The frontend for `omelette ladder`, which turns one word into another a
+// letter at a time
+
+use colors::ColorScheme;
+use config::LenRange;
+use history::History;
+use keymap::{Action, Keymap};
+use ladder::{Ladder, StepOutcome};
+use nc;
+use prompt;
+use rand::prelude::*;
+use seed::GameRng;
+use tui::{controls::*, element as el, event::Event, screen::Screen};
+use word_list::{WordList, WordlistForm};
+use {ErrorKind, Result};
+
+// Keys bound to any other action can be typed instead
+static LADDER_ACTIONS: &[Action] = &[
+  Action::Submit,
+  Action::Clear,
+  Action::Forfeit,
+  Action::Undo,
+  Action::HistoryPrev,
+  Action::HistoryNext,
+  Action::Left,
+  Action::Right,
+  Action::Home,
+  Action::End,
+  Action::DelLeft,
+  Action::DelRight,
+  Action::FocusNext,
+  Action::FocusPrev,
+  Action::Quit,
+];
+
+// Lays out a word ladder: the words so far, then a blank for each word still
+// needed, then the target.  Once the ladder is over, the blanks are filled in
+// with a solution instead.
+fn ladder_view<'a>(
+  words: &WordList,
+  ladder: &Ladder,
+  status: &'a el::ElemWrapper<TextView>,
+  word_box: &'a el::ElemWrapper<WordBox>,
+  (reveal_pair, hl_pair, hint_pair): (i32, i32, i32),
+) -> el::ElemRef<'a> {
+  let len = ladder.start().chars().count();
+
+  let match_box = |norm: Option<&str>| {
+    let form = match norm {
+      Some(n) => words.get_form(n).into_iter().next().unwrap_or(WordlistForm {
+        blanked: "_".repeat(len),
+        full: n.to_string(),
+      }),
+      None => WordlistForm {
+        blanked: "_".repeat(len),
+        full: String::new(),
+      },
+    };
+
+    let mut b = MatchBox::new(form, reveal_pair, hl_pair, hint_pair);
+
+    b.set_revealed(norm.is_some());
+    b
+  };
+
+  let mut boxes: Vec<MatchBox> =
+    ladder.chain().iter().map(|w| match_box(Some(w))).collect();
+
+  if let Some(b) = boxes.last_mut() {
+    b.set_style(MatchBoxStyle::Highlight);
+  }
+
+  if !ladder.solved() {
+    let rest = ladder
+      .solution()
+      .unwrap_or_else(|| vec![ladder.target().into()]);
+
+    if ladder.forfeited() {
+      for word in &rest {
+        let mut b = match_box(Some(word));
+
+        b.set_style(MatchBoxStyle::Reveal);
+        boxes.push(b);
+      }
+    } else {
+      for _ in 1..rest.len() {
+        boxes.push(match_box(None));
+      }
+
+      boxes.push(match_box(Some(ladder.target())));
+    }
+  }
+
+  let panel = el::wrap(WrapBox::new(
+    boxes.into_iter().map(|b| el::wrap(b) as el::ElemRef),
+    WrapMode::Cols,
+    WrapAlign::Begin,
+    WrapAlign::Middle,
+    0,
+    3,
+  ));
+
+  el::wrap(Grid::new(
+    vec![
+      (el::add_ref(status), (0, 0)),
+      (panel as el::ElemRef, (1, 0)),
+      (el::add_ref(word_box), (2, 0)),
+    ],
+    vec![
+      GridLength::Content,
+      GridLength::Dynamic(1.0),
+      GridLength::Content,
+    ],
+    vec![GridLength::Dynamic(1.0)],
+  ))
+}
+
+fn ladder_status(ladder: &Ladder, message: &str) -> Vec<String> {
+  vec![
+    format!("{} to {}", ladder.start(), ladder.target()),
+    format!("steps: {}  fewest: {}", ladder.steps(), ladder.min_steps()),
+    message.into(),
+  ]
+}
+
+// Plays word ladders until the player quits, picking lengths from the given
+// range or asking for them
+pub fn run(
+  words: &WordList,
+  colors: &ColorScheme,
+  keymap: &Keymap,
+  rng: &mut GameRng,
+  range: Option<LenRange>,
+) -> Result<()> {
+  let lens: Option<Vec<usize>> = match range {
+    Some(range) => {
+      let lens: Vec<_> = (range.min..range.max + 1)
+        .filter(|l| !words.get_ladders(l).is_empty())
+        .collect();
+
+      if lens.is_empty() {
+        return Err(ErrorKind::NoLadders(range.min, range.max).into());
+      }
+
+      Some(lens)
+    }
+    None => None,
+  };
+
+  let dictionary = words.all_words();
+  let mut len: Option<usize> = None;
+
+  'main: loop {
+    let ladder_seed = rng.gen();
+
+    let mut ladder = loop {
+      if let Some(ref lens) = lens {
+        len = Some(lens[rng.gen_range(0, lens.len())]);
+      } else if let None = len {
+        len = match prompt::word_len() {
+          Some(l) => Some(l),
+          None => return Ok(()),
+        };
+      }
+
+      let _len = len.unwrap();
+
+      match Ladder::random(words, &dictionary, _len, ladder_seed) {
+        Some(l) => break l,
+        None => return Err(ErrorKind::NoLadders(_len, _len).into()),
+      }
+    };
+
+    let screen = Screen::init();
+    let win = screen.win();
+
+    let keys = keymap.resolve().only(LADDER_ACTIONS);
+
+    let ghost_pair: i32 = 1;
+    nc::init_pair(ghost_pair as i16, colors.ghost.0, colors.ghost.1);
+
+    let bad_ghost_pair: i32 = 2;
+    nc::init_pair(
+      bad_ghost_pair as i16,
+      colors.bad_ghost.0,
+      colors.bad_ghost.1,
+    );
+
+    let auto_ghost_pair: i32 = 3;
+    nc::init_pair(
+      auto_ghost_pair as i16,
+      colors.auto_ghost.0,
+      colors.auto_ghost.1,
+    );
+
+    let reveal_pair: i32 = 4;
+    nc::init_pair(reveal_pair as i16, colors.reveal.0, colors.reveal.1);
+
+    let hl_pair: i32 = 5;
+    nc::init_pair(hl_pair as i16, colors.hl.0, colors.hl.1);
+
+    let hint_pair: i32 = 8;
+    nc::init_pair(hint_pair as i16, colors.hint.0, colors.hint.1);
+
+    let pairs = (reveal_pair, hl_pair, hint_pair);
+
+    let word_box = el::wrap(WordBox::open(
+      len.unwrap(),
+      ghost_pair,
+      bad_ghost_pair,
+      auto_ghost_pair,
+    ));
+
+    let status = el::wrap(TextView::new(ladder_status(&ladder, "")));
+
+    let mut ui_root = UiRoot::new(
+      win,
+      ladder_view(words, &ladder, &status, &word_box, pairs),
+    );
+
+    ui_root.resize();
+
+    let mut history = History::new();
+
+    while !ladder.is_over() {
+      // Set whenever the ladder changes and has to be laid out again
+      let mut message: Option<&str> = None;
+
+      let action = ui_root.run(win, &keys, |e| match e {
+        Event::Action(a) => Some(a),
+        _ => None,
+      });
+
+      match action {
+        Action::Quit => break 'main,
+        Action::Undo => {
+          if ladder.undo() {
+            message = Some("");
+          }
+        }
+        Action::Forfeit => {
+          ladder.forfeit();
+          message = Some("");
+        }
+        Action::Submit => {
+          let buf = word_box.borrow().buf().clone();
+
+          history.push(&buf);
+
+          match ladder.guess(&buf) {
+            StepOutcome::Step | StepOutcome::Solved => {
+              word_box.borrow_mut().clear();
+              message = Some("");
+            }
+            outcome => {
+              let why = match outcome {
+                StepOutcome::Repeated => "already in the ladder",
+                StepOutcome::NotAWord => "not a word",
+                _ => "not one step away",
+              };
+
+              status.borrow_mut().set_lines(ladder_status(&ladder, why));
+
+              let mut word_box = word_box.borrow_mut();
+
+              word_box.set_bad(!buf.is_empty());
+              word_box.clear();
+            }
+          }
+        }
+        Action::HistoryNext => {
+          if let Some(s) = history.next() {
+            word_box.borrow_mut().set_buf(s);
+          }
+        }
+        Action::HistoryPrev => {
+          let mut word_box = word_box.borrow_mut();
+
+          if let Some(s) = history.prev(word_box.buf()) {
+            word_box.set_buf(s);
+          }
+        }
+        _ => {}
+      }
+
+      if let Some(message) = message {
+        status.borrow_mut().set_lines(ladder_status(&ladder, message));
+        ui_root.set_child(ladder_view(
+          words, &ladder, &status, &word_box, pairs,
+        ));
+        ui_root.resize();
+      }
+    }
+
+    let result = if ladder.solved() {
+      format!("solved in {} step(s) - press any key", ladder.steps())
+    } else {
+      "one way it could have gone - press any key".to_string()
+    };
+
+    status.borrow_mut().set_lines(ladder_status(&ladder, &result));
+
+    if ui_root.read(win, &keys) == Some(Event::Action(Action::Quit)) {
+      break;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,90 @@
+// Everything shared between the game and omelette-server: the word database,
+// the rules of a round, settings and the wire protocol
+
+extern crate dirs;
+extern crate rand;
+extern crate serde;
+extern crate serde_json;
+
+#[macro_use]
+extern crate diesel;
+#[macro_use]
+extern crate error_chain;
+#[macro_use]
+extern crate serde_derive;
+
+// TODO: move the models and schema modules into the word_list module
+pub mod char_counts;
+pub mod config;
+pub mod difficulty;
+pub mod game;
+pub mod markov;
+pub mod mode;
+mod models;
+pub mod net;
+pub mod players;
+pub mod race;
+mod schema;
+pub mod score;
+pub mod seed;
+pub mod word_list;
+
+error_chain! {
+  foreign_links {
+    Diesel(diesel::result::Error);
+    DieselConnection(diesel::ConnectionError);
+    Io(::std::io::Error);
+  }
+
+  errors {
+    InvalidArg(expect: String) {
+      description("invalid arguments"),
+      display("invalid arguments: expected {}", expect),
+    }
+
+    ArgParse(msg: String) {
+      description("argument parsing failed"),
+      display("argument parsing failed: {}", msg),
+    }
+
+    UnknownArg(arg: String) {
+      description("unknown argument"),
+      display("unknown argument '{}' (try --help)", arg),
+    }
+
+    Config(path: String, msg: String) {
+      description("invalid config file"),
+      display("invalid config file {}: {}", path, msg),
+    }
+
+    NoDatabase(path: String) {
+      description("word database not found"),
+      display("word database {} not found", path),
+    }
+
+    NoLength {
+      description("no word length given"),
+      display("no word length given (try --len)"),
+    }
+
+    NoWords(min: usize, max: usize) {
+      description("no words found"),
+      display("no words found of length {} to {}", min, max),
+    }
+
+    NoLadders(min: usize, max: usize) {
+      description("no word ladders found"),
+      display("no word ladders found of length {} to {}", min, max),
+    }
+
+    Server(msg: String) {
+      description("server error"),
+      display("server error: {}", msg),
+    }
+
+    Disconnected {
+      description("disconnected"),
+      display("disconnected from the server"),
+    }
+  }
+}
@@ -0,0 +1,769 @@
+// The frontend for rounds played on this terminal, alone or passing the
+// keyboard around
+
+use colors::ColorScheme;
+use config::Config;
+use game::{self, GuessOutcome, Round};
+use history::History;
+use hot_seat::{finder_pair, show_players};
+use keymap::{Action, Keymap};
+use markov;
+use nc;
+use players::Players;
+use prompt;
+use rand::prelude::*;
+use save::SavedRound;
+use score::{self, HighScore, HighScores};
+use screens::{show_analysis, show_stats};
+use seed::{self, GameRng};
+use stats::{RoundRecord, Stats};
+use std::{
+  collections::HashMap,
+  fs::File,
+  io::{self, prelude::*},
+  time::{Duration, Instant},
+};
+use tui::{controls::*, element as el, event::Event, screen::Screen};
+use word_list::WordList;
+use Result;
+
+static TICK_MILLIS: i32 = 100;
+static DEFINITION_WIDTH: i32 = 30;
+
+// The buttons under the word box, and the actions they stand in for
+pub static BUTTONS: &[(&str, Action)] = &[
+  ("shuffle", Action::Shuffle),
+  ("clear", Action::Clear),
+  ("submit", Action::Submit),
+];
+
+// Keys bound to any other action can be typed instead
+static ROUND_ACTIONS: &[Action] = &[
+  Action::Submit,
+  Action::Shuffle,
+  Action::Clear,
+  Action::Forfeit,
+  Action::ToggleSort,
+  Action::Hint,
+  Action::PrevWord,
+  Action::NextWord,
+  Action::HistoryPrev,
+  Action::HistoryNext,
+  Action::Left,
+  Action::Right,
+  Action::Home,
+  Action::End,
+  Action::DelLeft,
+  Action::DelRight,
+  Action::Stats,
+  Action::PageUp,
+  Action::PageDown,
+  Action::FocusNext,
+  Action::FocusPrev,
+  Action::Quit,
+];
+
+fn unhighlight(match_boxes: Option<&Vec<el::ElemWrapper<MatchBox>>>) {
+  if let Some(b) = match_boxes {
+    for b in b {
+      let mut b = b.borrow_mut();
+
+      // Hinted words keep showing that they were hinted
+      let style = if b.hinted() > 0 {
+        MatchBoxStyle::Hint
+      } else {
+        MatchBoxStyle::Normal
+      };
+
+      b.set_style(style);
+    }
+  }
+}
+
+fn highlight(
+  match_boxes: &Vec<el::ElemWrapper<MatchBox>>,
+) -> &Vec<el::ElemWrapper<MatchBox>> {
+  for b in match_boxes {
+    b.borrow_mut().set_style(MatchBoxStyle::Highlight);
+  }
+
+  match_boxes
+}
+
+// Moves the selection to the previous or next word, wrapping around.  With
+// nothing selected yet, this starts from either end.
+fn step_selection<'a>(
+  words: &[&'a String],
+  selected: &Option<String>,
+  forward: bool,
+) -> Option<&'a String> {
+  if words.is_empty() {
+    return None;
+  }
+
+  let len = words.len();
+  let pos = selected
+    .as_ref()
+    .and_then(|s| words.iter().position(|w| *w == s));
+
+  let i = match pos {
+    Some(i) if forward => (i + 1) % len,
+    Some(i) => (i + len - 1) % len,
+    None if forward => 0,
+    None => len - 1,
+  };
+
+  Some(words[i])
+}
+
+fn show_definition(
+  definition_box: Option<&el::ElemWrapper<DefinitionBox>>,
+  words: &WordList,
+  norm: &str,
+) {
+  if let Some(d) = definition_box {
+    d.borrow_mut().set_word(norm, words.get_definition(norm));
+  }
+}
+
+fn reveal_all<'a, I>(match_boxes: I)
+where
+  I: IntoIterator<Item = &'a Vec<el::ElemWrapper<MatchBox>>>,
+{
+  for boxes in match_boxes {
+    for match_box in boxes {
+      let mut match_box = match_box.borrow_mut();
+
+      if !match_box.revealed() {
+        match_box.set_revealed(true);
+        match_box.set_style(MatchBoxStyle::Reveal);
+      }
+    }
+  }
+}
+
+// Plays rounds until the player quits, offering to pick up a saved round first.
+// lens is the range of lengths to pick from; without it, the player is asked.
+pub fn run(
+  words: &WordList,
+  config: &Config,
+  colors: &ColorScheme,
+  keymap: &Keymap,
+  rng: &mut GameRng,
+  seed: u64,
+  lens: Option<&Vec<usize>>,
+) -> Result<()> {
+  let mode = config.mode();
+  let difficulty = config.difficulty();
+
+  let scores_path = config.scores_path()?;
+  let mut high_scores = HighScores::load(&scores_path)?;
+
+  let stats = Stats::new(config.stats_path()?)?;
+  let player = score::player_name();
+
+  let save_path = config.save_path()?;
+
+  let mut resume = match SavedRound::load(&save_path) {
+    Ok(s) => s,
+    Err(e) => {
+      writeln!(io::stderr(), "couldn't load saved round: {}", e).unwrap();
+      None
+    }
+  };
+
+  // The word database may have changed since the round was saved
+  resume = resume
+    .filter(|s| !words.get_set(&s.key).is_empty())
+    .filter(prompt::resume);
+
+  // A save is only offered once, whether or not it's taken up
+  SavedRound::remove(&save_path)?;
+
+  let mut len: Option<usize> = None;
+
+  'main: loop {
+    let saved = resume.take();
+    let mode = saved.as_ref().map_or(mode, |s| s.game_mode());
+
+    let mut round = match saved {
+      Some(ref s) => {
+        len = Some(s.key_len());
+        s.resume(words)
+      }
+      None => {
+        let round_seed = rng.gen();
+
+        loop {
+          if let Some(ref lens) = lens {
+            len = Some(lens[rng.gen_range(0, lens.len())]);
+          } else if let None = len {
+            len = match prompt::word_len() {
+              Some(l) => Some(l),
+              None => return Ok(()),
+            };
+          }
+
+          let _len = len.unwrap();
+
+          match Round::random(words, _len, round_seed, difficulty) {
+            Some(r) => break r,
+            None => {
+              writeln!(io::stderr(), "no words found of length {}", _len)
+                .unwrap();
+              len = None;
+              continue;
+            }
+          }
+        }
+      }
+    };
+
+    // The UI keeps its own copies, since the round changes underneath it
+    let key = round.key().to_string();
+    let set = round.set().to_vec();
+
+    if let Some(ref path) = config.debug_log {
+      let mut file = File::create(path)?;
+      let table = game::letter_table(&set);
+
+      writeln!(file, "seed: {} (round {})", seed, round.seed())?;
+      writeln!(file, "table:")?;
+
+      for (from, tos) in &table {
+        for (to, freq) in tos {
+          writeln!(file, "  {} -> {}: {}", from, to, freq)?;
+        }
+      }
+
+      let markov = markov::Markov::new(table);
+
+      writeln!(file, "samples:")?;
+
+      // Samples come from their own Rng, seeded by the round, so that the log
+      // is reproducible without disturbing the Rng later rounds are drawn from
+      let mut log_rng = seed::rng_from_seed(round.seed());
+      let seeds: Vec<_> = markov.rand_seed(&mut log_rng).take(20).collect();
+
+      for s in seeds {
+        let line: String = markov.iter(s, &mut log_rng).take(40).collect();
+
+        writeln!(file, "{}", line)?;
+      }
+    }
+
+    let mut history = match saved {
+      Some(ref s) => History::from_entries(s.history.clone()),
+      None => History::new(),
+    };
+
+    // A resumed round keeps its own players, if any
+    let mut players = match saved {
+      Some(ref s) => s.players(),
+      None => config.players.as_ref().map(Players::new),
+    };
+
+    let screen = Screen::init();
+    let win = screen.win();
+
+    let keys = keymap.resolve();
+    let round_keys = keys.only(ROUND_ACTIONS);
+
+    let ghost_pair: i32 = 1;
+    nc::init_pair(ghost_pair as i16, colors.ghost.0, colors.ghost.1);
+    // nc::init_extended_pair(ghost_pair, 2, 0);
+
+    let bad_ghost_pair: i32 = 2;
+    nc::init_pair(
+      bad_ghost_pair as i16,
+      colors.bad_ghost.0,
+      colors.bad_ghost.1,
+    );
+
+    let auto_ghost_pair: i32 = 3;
+    nc::init_pair(
+      auto_ghost_pair as i16,
+      colors.auto_ghost.0,
+      colors.auto_ghost.1,
+    );
+
+    let reveal_pair: i32 = 4;
+    nc::init_pair(reveal_pair as i16, colors.reveal.0, colors.reveal.1);
+
+    let hl_pair: i32 = 5;
+    nc::init_pair(hl_pair as i16, colors.hl.0, colors.hl.1);
+
+    let best_pair: i32 = 6;
+    nc::init_pair(best_pair as i16, colors.best.0, colors.best.1);
+
+    let clock_low_pair: i32 = 7;
+    nc::init_pair(
+      clock_low_pair as i16,
+      colors.clock_low.0,
+      colors.clock_low.1,
+    );
+
+    let hint_pair: i32 = 8;
+    nc::init_pair(hint_pair as i16, colors.hint.0, colors.hint.1);
+
+    let player_pairs: Vec<i32> = colors
+      .players
+      .iter()
+      .enumerate()
+      .map(|(i, &(fg, bg))| {
+        let pair = 9 + i as i32;
+        nc::init_pair(pair as i16, fg, bg);
+        pair
+      })
+      .collect();
+
+    let word_box = el::wrap(WordBox::new(
+      key.clone(),
+      ghost_pair,
+      bad_ghost_pair,
+      auto_ghost_pair,
+    ));
+
+    let mut match_boxes: HashMap<&String, Vec<_>> = HashMap::new();
+
+    for norm in &set {
+      let forms = words.get_form(norm);
+
+      match_boxes.insert(
+        norm,
+        forms
+          .into_iter()
+          .map(|form| {
+            el::wrap(MatchBox::new(form, reveal_pair, hl_pair, hint_pair))
+          })
+          .collect(),
+      );
+    }
+
+    // Big sets run off the side of the screen, so they can be scrolled across
+    let match_box_panel = el::wrap(ScrollView::new(
+      el::wrap(WrapBox::new(
+        set
+          .iter()
+          .flat_map(|i| &match_boxes[i])
+          .map(|b| el::add_ref(b)),
+        WrapMode::Cols,
+        WrapAlign::Begin,
+        WrapAlign::Middle,
+        0,
+        3,
+      )),
+      ScrollAxis::Horizontal,
+    ));
+
+    let score_box = el::wrap(ScoreBox::new(
+      set.len(),
+      high_scores.best(mode, round.key_len()).map(|s| s.points),
+      best_pair,
+    ));
+
+    let clock_box = mode
+      .time_limit()
+      .map(|l| el::wrap(ClockBox::new(l, clock_low_pair)));
+
+    let bonus_box = el::wrap(BonusBox::new(hl_pair));
+
+    let definition_box = if words.has_definitions() {
+      Some(el::wrap(DefinitionBox::new(DEFINITION_WIDTH, hl_pair)))
+    } else {
+      None
+    };
+
+    let scoreboard = players
+      .as_ref()
+      .map(|_| el::wrap(Scoreboard::new(player_pairs.clone())));
+
+    let button_bar = el::wrap(ButtonBar::new(
+      BUTTONS.iter().map(|&(label, a)| (label.into(), a)).collect(),
+    ));
+
+    let mut hl_match_boxes: Option<&Vec<el::ElemWrapper<MatchBox>>> = None;
+    let mut selected: Option<String> = None; // The word being defined
+
+    let center_test = el::wrap(TestView::new(
+      el::add_ref(&word_box),
+      el::add_ref(&match_box_panel),
+      el::add_ref(&score_box),
+      clock_box.as_ref().map(|c| el::add_ref(c)),
+      el::add_ref(&bonus_box),
+      definition_box.as_ref().map(|d| el::add_ref(d)),
+      scoreboard.as_ref().map(|s| el::add_ref(s)),
+      Some(el::add_ref(&button_bar)),
+    ));
+
+    let mut ui_root = UiRoot::new(win, el::add_ref(&center_test));
+
+    ui_root.resize();
+
+    // Bring the UI up to date with a resumed round
+    for norm in round.found() {
+      for b in match_boxes.get(norm).map_or(&[][..], |b| &b[..]) {
+        let mut b = b.borrow_mut();
+
+        b.set_revealed(true);
+        b.set_finder_pair(finder_pair(players.as_ref(), &player_pairs, norm));
+      }
+    }
+
+    for (norm, count) in round.hints() {
+      for b in match_boxes.get(norm).map_or(&[][..], |b| &b[..]) {
+        b.borrow_mut().hint(*count);
+      }
+    }
+
+    {
+      let mut bonus_box = bonus_box.borrow_mut();
+
+      for word in round.bonus_words() {
+        let pair = finder_pair(players.as_ref(), &player_pairs, word);
+
+        bonus_box.push(word, pair);
+      }
+    }
+
+    show_players(scoreboard.as_ref(), players.as_ref());
+
+    {
+      let mut score_box = score_box.borrow_mut();
+      let score = round.score();
+
+      score_box.set_score(score.points(), score.found());
+      score_box.set_hints(score.hints());
+    }
+
+    if let Some(ref s) = saved {
+      word_box.borrow_mut().set_buf(&s.buf);
+    }
+
+    // A resumed round picks up the clock where it left off.  The time it had
+    // already taken is kept apart from started, since an Instant can't always
+    // go back that far.
+    let carried = saved
+      .as_ref()
+      .map_or(Duration::from_secs(0), |s| s.elapsed());
+    let mut started = Instant::now();
+
+    let mut deadline = mode.time_limit().map(|l| {
+      started + l.checked_sub(carried).unwrap_or(Duration::from_secs(0))
+    });
+
+    if deadline.is_some() {
+      nc::wtimeout(win, TICK_MILLIS);
+    }
+
+    while !round.is_over() {
+      if let Some(deadline) = deadline {
+        let now = Instant::now();
+
+        if now >= deadline {
+          unhighlight(hl_match_boxes);
+          reveal_all(match_boxes.values());
+          round.time_out();
+          break;
+        }
+
+        if let Some(ref clock_box) = clock_box {
+          clock_box.borrow_mut().set_remaining(deadline - now);
+        }
+      }
+
+      // Timing out waiting for a key comes back as no action at all, so that
+      // the clock gets checked
+      let action = ui_root.run(win, &round_keys, |e| match e {
+        Event::Action(a) => Some(Some(a)),
+        Event::Timeout => Some(None),
+        _ => None,
+      });
+
+      match action {
+        Some(Action::Quit) => {
+          // Save the round so it can be resumed next time
+          SavedRound::new(
+            &round,
+            mode,
+            word_box.borrow().buf(),
+            &history,
+            players.as_ref(),
+            carried + started.elapsed(),
+          ).save(&save_path)?;
+
+          break 'main;
+        }
+        Some(Action::Shuffle) => {
+          word_box
+            .borrow_mut()
+            .shuffle(|letters| round.shuffle_letters(letters));
+        }
+        Some(Action::Forfeit) => {
+          unhighlight(hl_match_boxes);
+
+          reveal_all(match_boxes.values());
+
+          round.forfeit();
+          break;
+        }
+        Some(Action::Submit) => {
+          unhighlight(hl_match_boxes);
+          bonus_box.borrow_mut().set_highlight(None);
+
+          {
+            let mut word_box = word_box.borrow_mut();
+            let buf = word_box.buf().clone();
+
+            history.push(&buf);
+
+            let outcome = round.guess(&buf);
+
+            match outcome {
+              GuessOutcome::Found(points) => {
+                if let Some(ref mut p) = players {
+                  p.credit(&buf, points);
+                }
+
+                let pair = finder_pair(players.as_ref(), &player_pairs, &buf);
+                let b = &match_boxes[&buf];
+
+                for b in b {
+                  let mut b = b.borrow_mut();
+
+                  b.set_revealed(true);
+                  b.set_finder_pair(pair);
+                  b.set_style(MatchBoxStyle::Reveal);
+                }
+
+                hl_match_boxes = Some(b);
+                selected = Some(buf.clone());
+                show_definition(definition_box.as_ref(), words, &buf);
+
+                // Clearing a timed round early is worth a bonus
+                if let Some(deadline) = deadline {
+                  let now = Instant::now();
+
+                  if now < deadline {
+                    let points = round.finish_early(deadline - now);
+
+                    if let Some(ref mut p) = players {
+                      p.award(points);
+                    }
+                  }
+                }
+              }
+              GuessOutcome::AlreadyFound => {
+                let b = &match_boxes[&buf];
+
+                for b in b {
+                  b.borrow_mut().set_style(MatchBoxStyle::Highlight);
+                }
+
+                hl_match_boxes = Some(b);
+                selected = Some(buf.clone());
+                show_definition(definition_box.as_ref(), words, &buf);
+              }
+              GuessOutcome::Bonus(points) => {
+                if let Some(ref mut p) = players {
+                  p.credit(&buf, points);
+                }
+
+                let pair = finder_pair(players.as_ref(), &player_pairs, &buf);
+                let mut bonus_box = bonus_box.borrow_mut();
+
+                bonus_box.push(&buf, pair);
+                bonus_box.set_highlight(Some(&buf));
+              }
+              GuessOutcome::AlreadyBonus => {
+                bonus_box.borrow_mut().set_highlight(Some(&buf));
+              }
+              GuessOutcome::Wrong => {}
+            }
+
+            word_box.set_bad(outcome == GuessOutcome::Wrong && buf.len() > 0);
+
+            // Players keep the turn for as long as they keep scoring
+            if let Some(ref mut p) = players {
+              match outcome {
+                GuessOutcome::Found(_) | GuessOutcome::Bonus(_) => {}
+                _ if buf.is_empty() => {}
+                _ => p.pass_turn(),
+              }
+            }
+
+            show_players(scoreboard.as_ref(), players.as_ref());
+
+            score_box
+              .borrow_mut()
+              .set_score(round.score().points(), round.score().found());
+
+            match outcome {
+              GuessOutcome::Found(_) | GuessOutcome::Bonus(_) => {
+                word_box.set_auto_sort(false);
+                word_box.clear();
+              }
+              _ if word_box.auto_sort() => {}
+              _ => word_box.clear(),
+            }
+          }
+        }
+        Some(Action::Hint) => {
+          let typed = word_box.borrow().buf().clone();
+
+          if let Some((norm, count)) = round.hint(&typed) {
+            for b in &match_boxes[&norm] {
+              b.borrow_mut().hint(count);
+            }
+
+            if let Some(ref mut p) = players {
+              p.add_hint();
+            }
+
+            show_players(scoreboard.as_ref(), players.as_ref());
+
+            let mut score_box = score_box.borrow_mut();
+            let score = round.score();
+
+            score_box.set_score(score.points(), score.found());
+            score_box.set_hints(score.hints());
+          }
+        }
+        Some(a @ Action::PrevWord) | Some(a @ Action::NextWord) => {
+          // Select a found word to define
+          let found: Vec<_> =
+            set.iter().filter(|n| round.is_found(n)).collect();
+          let next = a == Action::NextWord;
+
+          if let Some(norm) = step_selection(&found, &selected, next) {
+            unhighlight(hl_match_boxes);
+            hl_match_boxes = Some(highlight(&match_boxes[norm]));
+            selected = Some(norm.clone());
+            show_definition(definition_box.as_ref(), words, norm);
+          }
+        }
+        Some(Action::HistoryNext) => {
+          if let Some(s) = history.next() {
+            word_box.borrow_mut().set_buf(s);
+          }
+        }
+        Some(Action::HistoryPrev) => {
+          let mut word_box = word_box.borrow_mut();
+
+          if let Some(s) = history.prev(word_box.buf()) {
+            word_box.set_buf(s);
+          }
+        }
+        Some(Action::ToggleSort) => {
+          let mut word_box = word_box.borrow_mut();
+          let val = !word_box.auto_sort();
+          word_box.set_auto_sort(val);
+        }
+        Some(Action::Stats) => {
+          let paused = Instant::now();
+
+          show_stats(win, &mut ui_root, &keys, &stats, &player)?;
+
+          // The clock stops while the stats are up
+          let paused = paused.elapsed();
+
+          started = started + paused;
+          deadline = deadline.map(|d| d + paused);
+
+          if deadline.is_some() {
+            nc::wtimeout(win, TICK_MILLIS);
+          }
+        }
+        _ => {}
+      }
+    }
+
+    match players {
+      Some(ref p) => {
+        // Hot-seat rounds belong to no one player, so they're kept out of the
+        // stats and high scores
+        if let Some(ref s) = scoreboard {
+          s.borrow_mut().set_winners(p.leaders());
+        }
+      }
+      None => {
+        stats.record(&RoundRecord {
+          player: &player,
+          key: &key,
+          mode,
+          score: round.score(),
+          time: carried + started.elapsed(),
+          forfeit: round.forfeited(),
+          words: set.iter().map(|n| (n.as_str(), round.is_found(n))).collect(),
+        })?;
+
+        if round.score().points() > 0 {
+          let score = HighScore::new(&key, round.score());
+
+          high_scores.insert(mode, round.key_len(), score);
+          high_scores.save(&scores_path)?;
+        }
+      }
+    }
+
+    // Go over what was missed before looking at the board again
+    let title = format!(
+      "{}: found {}/{}",
+      key,
+      round.score().found(),
+      round.score().total()
+    );
+    let analysis = round.analyze();
+
+    show_analysis(win, &mut ui_root, &keys, &title, &analysis);
+
+    loop {
+      nc::wtimeout(win, -1);
+
+      // Any key other than these moves on to the next round
+      let action = match ui_root.read(win, &keys) {
+        Some(Event::Resize) => {
+          ui_root.resize();
+          continue;
+        }
+        Some(e @ Event::Scroll(..))
+        | Some(e @ Event::Action(Action::PageUp))
+        | Some(e @ Event::Action(Action::PageDown)) => {
+          ui_root.dispatch(e);
+          continue;
+        }
+        Some(Event::Action(a)) => Some(a),
+        _ => None,
+      };
+
+      match action {
+        Some(Action::Quit) => break 'main,
+        Some(Action::Stats) => {
+          show_stats(win, &mut ui_root, &keys, &stats, &player)?;
+        }
+        Some(Action::Analysis) => {
+          show_analysis(win, &mut ui_root, &keys, &title, &analysis);
+        }
+        Some(a @ Action::PrevWord) | Some(a @ Action::NextWord) => {
+          // Every word is revealed by now, so any of them can be selected
+          let all: Vec<_> = set.iter().collect();
+          let next = a == Action::NextWord;
+
+          if let Some(norm) = step_selection(&all, &selected, next) {
+            unhighlight(hl_match_boxes);
+            hl_match_boxes = Some(highlight(&match_boxes[norm]));
+            selected = Some(norm.clone());
+            show_definition(definition_box.as_ref(), words, norm);
+          }
+        }
+        _ => break,
+      }
+    }
+
+    if round.forfeited() || round.timed_out() {
+      len = None;
+    }
+  }
+
+  Ok(())
+}
@@ -1,347 +1,161 @@
 extern crate ncurses as nc;
+extern crate omelette;
 extern crate rand;
-extern crate regex;
+extern crate serde;
+extern crate serde_json;
 
 #[macro_use]
 extern crate diesel;
 #[macro_use]
-extern crate lazy_static;
-
-// TODO: move the models and schema modules into the word_list module
-mod markov;
-mod models;
-mod schema;
+extern crate serde_derive;
+
+mod client_config;
+mod colors;
+mod headless;
+mod history;
+mod hot_seat;
+mod keymap;
+mod ladder;
+mod ladder_ui;
+mod local;
+mod mouse;
+mod online;
+mod prompt;
+mod save;
+mod screens;
+mod stats;
 mod tui;
-mod word_list;
 
-use rand::prelude::*;
+use omelette::{
+  char_counts, config, difficulty, game, markov, mode, net, players, score,
+  seed, word_list, ErrorKind, Result,
+};
+
+use client_config::{Args, ClientConfig};
 use std::{
-  collections::{HashMap, HashSet},
-  fs::File,
+  env,
   io::{self, prelude::*},
   panic,
 };
-use tui::{
-  controls::*,
-  element::{self as el, Element},
-};
 use word_list::WordList;
 
-fn dump_line(win: nc::WINDOW, y: i32, line: &str) {
-  nc::wmove(win, y, 0);
-  nc::wclrtoeol(win);
-  nc::mvwaddstr(win, y, 0, line);
-  nc::wrefresh(win);
-}
+static SOLVE_WIDTH: usize = 80;
 
-fn main() {
+fn run() -> Result<()> {
   panic::catch_unwind(|| {
     nc::endwin();
   }).unwrap();
 
-  let words = WordList::new("etc/words.sqlite3");
-
-  let mut len: Option<usize> = None;
-
-  'main: loop {
-    let key;
-    let set = {
-      let mut keys = loop {
-        if let None = len {
-          let mut len_str = String::new();
-
-          write!(io::stderr(), "word length: ").unwrap();
-          io::stderr().flush().unwrap();
-
-          if io::stdin().read_line(&mut len_str).unwrap() == 0 {
-            writeln!(io::stderr(), "").unwrap();
-            return;
-          }
-
-          len = Some(match len_str.trim().parse() {
-            Ok(l) => l,
-            Err(e) => {
-              writeln!(io::stderr(), "invalid number: {}", e).unwrap();
-              continue;
-            }
-          });
-        }
-
-        let _len = len.unwrap();
-
-        let keys = words.get_set_keys(&_len);
-
-        match keys.len() {
-          0 => {
-            writeln!(io::stderr(), "no words found of length {}", _len)
-              .unwrap();
-            len = None;
-            continue;
-          }
-          _ => break keys,
-        }
-      };
-
-      let nkeys = keys.len();
-
-      key = keys.remove(rand::thread_rng().gen_range(0, nkeys));
-
-      words.get_set(&key)
-    };
-
-    let markov = {
-      use std::collections::hash_map::Entry::*;
-
-      let mut table = markov::analyze_corpus(
-        set.iter().map(|s| ((s.len() as f64).powf(3.4), s.chars())),
-      );
-      let chars: HashSet<_> = set.iter().flat_map(|s| s.chars()).collect();
-
-      let pad = table
-        .values()
-        .flat_map(|t| t.values())
-        .fold(0.0, |s, c| s + c) / 100.0;
-
-      for chr in &chars {
-        let tos = match table.entry(*chr) {
-          Vacant(v) => v.insert(HashMap::new()),
-          Occupied(o) => o.into_mut(),
-        };
-
-        for chr in &chars {
-          match tos.entry(*chr) {
-            Vacant(v) => {
-              v.insert(pad);
-            }
-            Occupied(o) => {
-              let o = o.into_mut();
-              *o = *o + pad;
-            }
-          }
-        }
-      }
+  // Without this, ncurses can't read or draw anything beyond ASCII
+  nc::setlocale(nc::LcCategory::all, "");
 
-      let mut file = File::create("freq.log").unwrap();
+  let args = Args::parse(env::args())?;
 
-      writeln!(file, "table:").unwrap();
+  if args.help {
+    println!("{}", client_config::USAGE);
+    return Ok(());
+  }
 
-      for (from, tos) in &table {
-        for (to, freq) in tos {
-          writeln!(file, "  {} -> {}: {}", from, to, freq).unwrap();
-        }
-      }
+  let client =
+    ClientConfig::load(args.config_path.as_ref().map(|p| p.as_path()))?
+      .merge(args.config);
+
+  let colors = client.colors();
+  let keymap = client.keymap();
+  let config = client.config;
 
-      let markov = markov::Markov::new(table);
+  let difficulty = config.difficulty();
 
-      writeln!(file, "samples:").unwrap();
+  if args.keys {
+    for line in keymap.lines() {
+      println!("{}", line);
+    }
 
-      for s in markov.rand_seed().take(20) {
-        let line: String = markov.iter(s).take(40).collect();
+    return Ok(());
+  }
 
-        writeln!(file, "{}", line).unwrap();
-      }
+  // Online rounds come from the server, so none of the settings below apply
+  if let Some(ref addr) = args.join {
+    let name = args.name.clone().unwrap_or_else(score::player_name);
 
-      markov
-    };
+    return online::run(&net::with_port(addr), &name, &colors, &keymap);
+  }
 
-    let mut remain: HashSet<&String> = set.iter().collect();
+  let seed = match args.seed {
+    Some(s) => s,
+    None if config.daily() => seed::daily_seed(),
+    None => rand::random(),
+  };
 
-    let win = nc::initscr();
-    nc::start_color();
-    nc::cbreak();
-    nc::noecho();
-    nc::keypad(win, true);
+  // Each round gets its own Rng seeded from this one, so that a round plays out
+  // the same no matter what happened in the previous ones
+  let mut rng = seed::rng_from_seed(seed);
 
-    let ghost_pair: i32 = 1;
-    nc::init_pair(ghost_pair as i16, 2, 0);
-    // nc::init_extended_pair(ghost_pair, 2, 0);
+  let db_path = config.db_path();
 
-    let bad_ghost_pair: i32 = 2;
-    nc::init_pair(bad_ghost_pair as i16, 1, 0);
+  if !db_path.exists() {
+    return Err(ErrorKind::NoDatabase(db_path.display().to_string()).into());
+  }
 
-    let auto_ghost_pair: i32 = 3;
-    nc::init_pair(auto_ghost_pair as i16, 3, 0);
+  let words = WordList::new(&db_path.to_string_lossy());
 
-    let reveal_pair: i32 = 4;
-    nc::init_pair(reveal_pair as i16, 3, 0);
+  if let Some(ref letters) = args.solve {
+    let letters: String = letters
+      .to_lowercase()
+      .chars()
+      .filter(|c| c.is_alphanumeric())
+      .collect();
+    let analysis = game::solve(&words, &letters);
 
-    let hl_pair: i32 = 5;
-    nc::init_pair(hl_pair as i16, 2, 0);
+    if analysis.by_len.is_empty() {
+      println!("no set words for '{}'", letters);
+    }
 
-    let word_box = el::wrap(WordBox::new(
-      key.clone(),
-      ghost_pair,
-      bad_ghost_pair,
-      auto_ghost_pair,
-    ));
+    for line in analysis.lines(true, SOLVE_WIDTH) {
+      println!("{}", line);
+    }
 
-    let mut match_boxes: HashMap<&String, Vec<_>> = HashMap::new();
+    return Ok(());
+  }
 
-    for norm in &set {
-      let forms = words.get_form(norm);
+  if args.ladder {
+    return ladder_ui::run(&words, &colors, &keymap, &mut rng, config.word_len);
+  }
 
-      match_boxes.insert(
-        norm,
-        forms
-          .into_iter()
-          .map(|form| el::wrap(MatchBox::new(form, reveal_pair, hl_pair)))
-          .collect(),
-      );
-    }
+  // If a length range was given, only pick from lengths that have any words
+  let lens: Option<Vec<usize>> = match config.word_len {
+    Some(range) => {
+      let lens: Vec<_> = (range.min..range.max + 1)
+        .filter(|l| !words.get_set_keys(l, difficulty).is_empty())
+        .collect();
 
-    let match_box_panel = el::wrap(WrapBox::new(
-      set
-        .iter()
-        .flat_map(|i| &match_boxes[i])
-        .map(|b| el::add_ref(b)),
-      WrapMode::Cols,
-      WrapAlign::Begin,
-      3,
-    ));
-
-    let mut hl_match_boxes: Option<&Vec<el::ElemWrapper<MatchBox>>> = None;
-
-    let center_test = el::wrap(TestView::new(
-      el::add_ref(&word_box),
-      el::add_ref(&match_box_panel),
-    ));
-
-    let ui_root = UiRoot::new(win, el::add_ref(&center_test));
-
-    ui_root.resize();
-
-    while remain.len() > 0 {
-      // TODO: handle modifier keys better
-      // TODO: up and down should be history controls, not text editing controls
-      match nc::wgetch(win) {
-        0x04 => break 'main,
-        0x09 => word_box.borrow_mut().shuffle(&markov), // HT
-        0x17 => word_box.borrow_mut().clear(),          // ETB (ctrl+bksp)
-        0x1B => {
-          // ESC
-
-          if let Some(b) = hl_match_boxes {
-            for b in b {
-              let mut b = b.borrow_mut();
-
-              b.set_style(MatchBoxStyle::Normal);
-            }
-          }
-
-          for boxes in match_boxes.values() {
-            for match_box in boxes {
-              let mut match_box = match_box.borrow_mut();
-
-              if !match_box.revealed() {
-                match_box.set_revealed(true);
-                match_box.set_style(MatchBoxStyle::Reveal);
-              }
-            }
-          }
-
-          word_box.borrow_mut().render_cur();
-
-          match nc::wgetch(win) {
-            0x04 => break 'main,
-            _ => {}
-          }
-
-          len = None;
-          break;
-        }
-        0x0A => {
-          // EOL
-          if let Some(b) = hl_match_boxes {
-            for b in b {
-              let mut b = b.borrow_mut();
-
-              b.set_style(MatchBoxStyle::Normal);
-            }
-          }
-
-          {
-            let mut word_box = word_box.borrow_mut();
-
-            remain.remove(word_box.buf());
-
-            let success = match match_boxes.get(word_box.buf()) {
-              Some(b) => {
-                hl_match_boxes = Some(b);
-                word_box.set_bad(false);
-
-                let mut success = true;
-
-                for b in b {
-                  let mut b_ref = b.borrow_mut();
-
-                  if b_ref.revealed() {
-                    b_ref.set_style(MatchBoxStyle::Highlight);
-                    success = false;
-                  } else {
-                    b_ref.set_revealed(true);
-                    b_ref.set_style(MatchBoxStyle::Reveal);
-                  }
-                }
-
-                success
-              }
-              None => {
-                let bad = word_box.buf().len() > 0;
-                word_box.set_bad(bad);
-                false
-              }
-            };
-
-            if success {
-              word_box.set_auto_sort(false);
-              word_box.clear();
-            } else if word_box.auto_sort() {
-              word_box.render_cur();
-            } else {
-              word_box.clear();
-            }
-          }
-        }
-        0x7F => word_box.borrow_mut().del_left(), // DEL (bksp)
-        nc::KEY_DOWN => word_box.borrow_mut().end(),
-        nc::KEY_UP => word_box.borrow_mut().home(),
-        nc::KEY_LEFT => word_box.borrow_mut().left(),
-        nc::KEY_RIGHT => word_box.borrow_mut().right(),
-        nc::KEY_HOME => word_box.borrow_mut().home(),
-        nc::KEY_BACKSPACE => word_box.borrow_mut().clear(), // (shift+bksp/ctrl+bksp)
-        nc::KEY_DC => word_box.borrow_mut().del_right(),
-        nc::KEY_BTAB => {
-          let mut word_box = word_box.borrow_mut();
-          let val = !word_box.auto_sort();
-          word_box.set_auto_sort(val);
-        } // (shift+tab)
-        nc::KEY_END => word_box.borrow_mut().end(),
-        nc::KEY_RESIZE => ui_root.resize(),
-        0o1051 => word_box.borrow_mut().home(), // ctrl+left somehow?
-        0o1070 => word_box.borrow_mut().end(),  // ctrl+right somehow?
-        ch => {
-          let mut word_box = word_box.borrow_mut();
-
-          if ch < nc::KEY_MIN {
-            let ch = ch as u8 as char;
-
-            if !ch.is_control() {
-              let s = ch.to_lowercase().to_string();
-              word_box.put(&s);
-            } else {
-              // dump_line(win, 3, &ch.escape_unicode().to_string());
-              // word_box.render_cur();
-            }
-          } else {
-            // dump_line(win, 4, &ch.to_string());
-            // word_box.render_cur();
-          }
-        }
+      if lens.is_empty() {
+        return Err(ErrorKind::NoWords(range.min, range.max).into());
       }
+
+      Some(lens)
     }
+    None => None,
+  };
 
-    nc::endwin();
+  if args.headless {
+    return headless::run(&words, &mut rng, lens.as_ref(), difficulty);
   }
 
-  nc::endwin();
+  local::run(
+    &words,
+    &config,
+    &colors,
+    &keymap,
+    &mut rng,
+    seed,
+    lens.as_ref(),
+  )
+}
+
+fn main() {
+  match run() {
+    Ok(_) => return,
+    Err(e) => writeln!(io::stderr(), "an error occurred: {}", e).unwrap(),
+  }
 }
@@ -1,11 +1,13 @@
-use rand::{self, prelude::*};
+use rand::prelude::*;
 use std::{
   cmp::Ordering,
   collections::{BTreeMap, Bound, HashMap},
   hash::Hash,
 };
 
-type FreqTable<T> = HashMap<T, HashMap<T, f64>>;
+// NB: this is a BTreeMap so that iteration order (and therefore the output of a
+//     seeded Rng) is stable
+pub type FreqTable<T> = BTreeMap<T, BTreeMap<T, f64>>;
 
 #[derive(Clone, Copy, PartialEq, PartialOrd)]
 struct TotalDouble(f64);
@@ -20,19 +22,19 @@ impl Ord for TotalDouble {
 
 pub struct Markov<T>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
 {
-  table: HashMap<T, BTreeMap<TotalDouble, T>>,
+  table: BTreeMap<T, BTreeMap<TotalDouble, T>>,
 }
 
 impl<T> Markov<T>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
 {
   pub fn new(freq: FreqTable<T>) -> Self {
-    let mut table = HashMap::new();
+    let mut table = BTreeMap::new();
 
     for (from, tos) in freq {
       let mut fold = 0.0;
@@ -56,23 +58,31 @@ where
     Self { table }
   }
 
-  pub fn iter<'a>(&'a self, seed: &'a T) -> MarkovIter<'a, T>
+  pub fn iter<'a, 'r, R>(
+    &'a self,
+    seed: &'a T,
+    rng: &'r mut R,
+  ) -> MarkovIter<'a, 'r, T, R>
   where
     T: 'a,
+    R: Rng,
   {
     MarkovIter {
       chain: self,
       state: Some(seed),
+      rng,
     }
   }
 
-  pub fn iter_counted<'a>(
+  pub fn iter_counted<'a, 'r, R>(
     &'a self,
     seed: &'a T,
     mut remain: HashMap<T, usize>,
-  ) -> MarkovIterCounted<'a, T>
+    rng: &'r mut R,
+  ) -> MarkovIterCounted<'a, 'r, T, R>
   where
     T: 'a,
+    R: Rng,
   {
     match remain.get_mut(seed) {
       Some(n) => if *n > 0 {
@@ -90,31 +100,41 @@ where
       state: Some(seed),
       remain,
       nremain,
+      rng,
     }
   }
 
-  pub fn rand_seed<'a>(&'a self) -> MarkovRandSeed<'a, T> {
+  pub fn rand_seed<'a, 'r, R>(
+    &'a self,
+    rng: &'r mut R,
+  ) -> MarkovRandSeed<'a, 'r, T, R>
+  where
+    R: Rng,
+  {
     MarkovRandSeed {
       keys: self.table.keys().collect(),
+      rng,
     }
   }
 }
 
-pub struct MarkovIter<'a, T>
+pub struct MarkovIter<'a, 'r, T, R>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
   T: 'a,
+  R: Rng + 'r,
 {
   chain: &'a Markov<T>,
   state: Option<&'a T>,
-  // TODO: keep an Rng handy
+  rng: &'r mut R,
 }
 
-impl<'a, T> Iterator for MarkovIter<'a, T>
+impl<'a, 'r, T, R> Iterator for MarkovIter<'a, 'r, T, R>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
+  R: Rng,
 {
   type Item = &'a T;
 
@@ -123,7 +143,7 @@ where
 
     if let Some(state) = state {
       self.state = self.chain.table.get(state).map(|map| {
-        let f = TotalDouble(rand::thread_rng().gen_range(0.0, 1.0));
+        let f = TotalDouble(self.rng.gen_range(0.0, 1.0));
 
         map
           .range((Bound::Unbounded, Bound::Included(f)))
@@ -137,24 +157,26 @@ where
   }
 }
 
-pub struct MarkovIterCounted<'a, T>
+pub struct MarkovIterCounted<'a, 'r, T, R>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
   T: 'a,
+  R: Rng + 'r,
 {
   chain: &'a Markov<T>,
   state: Option<&'a T>,
   remain: HashMap<T, usize>,
   nremain: usize,
-  // TODO: keep an Rng handy
+  rng: &'r mut R,
 }
 
-impl<'a, T> Iterator for MarkovIterCounted<'a, T>
+impl<'a, 'r, T, R> Iterator for MarkovIterCounted<'a, 'r, T, R>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
   T: 'a,
+  R: Rng,
 {
   type Item = &'a T;
 
@@ -166,7 +188,7 @@ where
       //       probably be fixed by constructing a temporary state table
       self.state = if self.nremain > 0 {
         self.chain.table.get(state).map(|map| loop {
-          let f = TotalDouble(rand::thread_rng().gen_range(0.0, 1.0));
+          let f = TotalDouble(self.rng.gen_range(0.0, 1.0));
 
           let (_, val) = map
             .range((Bound::Unbounded, Bound::Included(f)))
@@ -190,35 +212,74 @@ where
   }
 }
 
-pub struct MarkovRandSeed<'a, T>
+pub struct MarkovRandSeed<'a, 'r, T, R>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
   T: 'a,
+  R: Rng + 'r,
 {
   keys: Vec<&'a T>,
-  // TODO: keep an Rng handy
+  rng: &'r mut R,
 }
 
-impl<'a, T> Iterator for MarkovRandSeed<'a, T>
+impl<'a, 'r, T, R> Iterator for MarkovRandSeed<'a, 'r, T, R>
 where
-  T: Eq,
+  T: Ord,
   T: Hash,
   T: 'a,
+  R: Rng,
 {
   type Item = &'a T;
 
   fn next(&mut self) -> Option<Self::Item> {
-    let i = rand::thread_rng().gen_range(0, self.keys.len());
+    let i = self.rng.gen_range(0, self.keys.len());
     Some(self.keys[i])
   }
 }
 
+// Reorders the characters of s by walking the chain from a random one of them,
+// so that characters that often appear together tend to end up together
+pub fn shuffle_str<R: Rng>(
+  markov: &Markov<char>,
+  s: &str,
+  rng: &mut R,
+) -> String {
+  use std::collections::hash_map::Entry::*;
+
+  if s.is_empty() {
+    return String::new();
+  }
+
+  let mut remain: HashMap<char, usize> = HashMap::new();
+
+  for chr in s.chars() {
+    match remain.entry(chr) {
+      Vacant(v) => {
+        v.insert(1);
+      }
+      Occupied(o) => {
+        let o = o.into_mut();
+        *o = *o + 1;
+      }
+    }
+  }
+
+  let seed = markov
+    .rand_seed(rng)
+    .filter(|c| s.contains(**c))
+    .next()
+    .unwrap();
+
+  // TODO: keep an eye on this, it doesn't seem correct
+  markov.iter_counted(seed, remain, rng).collect()
+}
+
 pub fn analyze_corpus<I, J, T>(i: I) -> FreqTable<T>
 where
   I: IntoIterator<Item = (f64, J)>,
   J: IntoIterator<Item = T>,
-  T: Eq,
+  T: Ord,
   T: Hash,
   T: Clone,
 {
@@ -228,11 +289,11 @@ where
     let mut prev = None;
 
     for el in j {
-      use std::collections::hash_map::Entry::*;
+      use std::collections::btree_map::Entry::*;
 
       if let Some(prev) = prev {
         match match table.entry(prev) {
-          Vacant(v) => v.insert(HashMap::new()),
+          Vacant(v) => v.insert(BTreeMap::new()),
           Occupied(o) => o.into_mut(),
         }.entry(el.clone())
         {
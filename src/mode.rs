@@ -0,0 +1,37 @@
+use std::{str::FromStr, time::Duration};
+
+pub static DEFAULT_BLITZ_SECS: u64 = 120;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameMode {
+  Normal,
+  Blitz(Duration), // The time limit for each round
+}
+
+impl GameMode {
+  pub fn time_limit(&self) -> Option<Duration> {
+    match self {
+      GameMode::Normal => None,
+      GameMode::Blitz(d) => Some(*d),
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      GameMode::Normal => "normal",
+      GameMode::Blitz(_) => "blitz",
+    }
+  }
+}
+
+impl FromStr for GameMode {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, String> {
+    match s {
+      "normal" => Ok(GameMode::Normal),
+      "blitz" => Ok(GameMode::Blitz(Duration::from_secs(DEFAULT_BLITZ_SECS))),
+      s => Err(format!("unknown game mode '{}'", s)),
+    }
+  }
+}
@@ -0,0 +1,59 @@
+// Mouse input, for playing the way the phone original is played.  Only presses
+// of the left button are reported, so that a click takes effect right away,
+// along with turns of the wheel (which terminals report as presses of buttons 4
+// and 5).
+
+use nc;
+use tui::core::Point;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Press {
+  Left,
+  WheelUp,
+  WheelDown,
+}
+
+pub fn enable() {
+  nc::mousemask(
+    (nc::BUTTON1_PRESSED | nc::BUTTON4_PRESSED | nc::BUTTON5_PRESSED)
+      as nc::mmask_t,
+    None,
+  );
+  nc::mouseinterval(0);
+}
+
+// Reads the position and kind of a press after wgetch() gives KEY_MOUSE.
+// Returns None for anything else.
+pub fn read_press() -> Option<(Point, Press)> {
+  let mut event = nc::MEVENT {
+    id: 0,
+    x: 0,
+    y: 0,
+    z: 0,
+    bstate: 0,
+  };
+
+  if nc::getmouse(&mut event) != nc::OK {
+    return None;
+  }
+
+  let pressed = |b: i32| event.bstate & b as nc::mmask_t != 0;
+
+  let press = if pressed(nc::BUTTON1_PRESSED) {
+    Press::Left
+  } else if pressed(nc::BUTTON4_PRESSED) {
+    Press::WheelUp
+  } else if pressed(nc::BUTTON5_PRESSED) {
+    Press::WheelDown
+  } else {
+    return None;
+  };
+
+  Some((
+    Point {
+      x: event.x,
+      y: event.y,
+    },
+    press,
+  ))
+}
@@ -0,0 +1,81 @@
+// The wire protocol spoken between omelette-server and `omelette join`.  Each
+// message is one line of JSON, in either direction.  See the README for the
+// details.
+
+pub static DEFAULT_PORT: u16 = 4747;
+
+// Adds the default port to an address that doesn't give one
+pub fn with_port(addr: &str) -> String {
+  if addr.contains(':') {
+    addr.into()
+  } else {
+    format!("{}:{}", addr, DEFAULT_PORT)
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum ClientMessage {
+  Join { name: String }, // Has to come first
+  Guess { word: String },
+  GiveUp,
+  NewRound,
+  State,
+  Quit,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+  State {
+    state: NetState,
+  },
+  Guess {
+    word: String,
+    result: String,
+    points: u32,
+  },
+  Claim {
+    player: String,
+    word: String,
+    points: u32,
+  },
+  Error {
+    message: String,
+  },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NetState {
+  pub round: u64, // Counts up from 1, so clients can tell a new round apart
+  pub letters: String,
+  pub words: Vec<NetWord>,
+  pub bonus: Vec<NetBonus>,
+  pub players: Vec<NetPlayer>,
+  pub give_up: Vec<String>, // Everyone who has voted to give up so far
+  pub over: bool,
+}
+
+// One form of a word in the set.  The word is only given once it's been found,
+// or once the round is over.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NetWord {
+  pub blanked: String,
+  pub word: Option<String>, // As it would be typed
+  pub full: Option<String>, // As it's displayed
+  pub player: Option<String>, // Whoever found it
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NetBonus {
+  pub word: String,
+  pub player: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NetPlayer {
+  pub name: String,
+  pub points: u32,
+  pub found: usize, // Set and bonus words
+  pub connected: bool,
+}
@@ -0,0 +1,442 @@
+// The frontend for `omelette join`, which races other players on an
+// omelette-server.  Letters are typed and edited the same way as in a normal
+// round, but guesses are checked by the server, and each word goes to whoever
+// gets it in first.
+
+use colors::ColorScheme;
+use history::History;
+use keymap::{Action, KeyCodes, Keymap};
+use local::BUTTONS;
+use nc;
+use net::{ClientMessage, NetState, ServerMessage};
+use rand::{self, Rng};
+use serde_json;
+use std::{
+  io::{prelude::*, BufReader},
+  net::TcpStream,
+  sync::mpsc::{channel, Sender, TryRecvError},
+  thread,
+};
+use tui::{controls::*, element as el, event::Event, screen::Screen};
+use word_list::WordlistForm;
+use {ErrorKind, Result};
+
+static TICK_MILLIS: i32 = 100;
+
+// Keys bound to any other action can be typed instead
+static ONLINE_ACTIONS: &[Action] = &[
+  Action::Submit,
+  Action::Shuffle,
+  Action::Clear,
+  Action::Forfeit,
+  Action::HistoryPrev,
+  Action::HistoryNext,
+  Action::Left,
+  Action::Right,
+  Action::Home,
+  Action::End,
+  Action::DelLeft,
+  Action::DelRight,
+  Action::PageUp,
+  Action::PageDown,
+  Action::FocusNext,
+  Action::FocusPrev,
+  Action::Quit,
+];
+
+struct Pairs {
+  reveal: i32,
+  hl: i32,
+  hint: i32,
+  best: i32,
+  players: Vec<i32>,
+}
+
+impl Pairs {
+  // Players beyond the number of colors share them
+  fn player(&self, state: &NetState, name: &str) -> Option<i32> {
+    state
+      .players
+      .iter()
+      .position(|p| p.name == name)
+      .map(|i| self.players[i % self.players.len()])
+  }
+}
+
+fn send(mut stream: &TcpStream, msg: &ClientMessage) -> Result<()> {
+  writeln!(stream, "{}", serde_json::to_string(msg).unwrap())?;
+
+  Ok(())
+}
+
+// Passes messages from the server along until it hangs up, then sends None
+fn read_server(stream: TcpStream, messages: Sender<Option<ServerMessage>>) {
+  for line in BufReader::new(stream).lines() {
+    let line = match line {
+      Ok(l) => l,
+      Err(_) => break,
+    };
+
+    if let Ok(msg) = serde_json::from_str(&line) {
+      if messages.send(Some(msg)).is_err() {
+        return;
+      }
+    }
+  }
+
+  messages.send(None).ok();
+}
+
+fn status(
+  state: &NetState,
+  message: Option<&str>,
+  keys: &KeyCodes,
+) -> Vec<String> {
+  let line = match message {
+    Some(m) => m.to_string(),
+    None if state.over => format!(
+      "round over - press {} for a new round",
+      keys.name(Action::Submit)
+    ),
+    None if !state.give_up.is_empty() => format!(
+      "giving up: {} ({} to agree)",
+      state.give_up.join(", "),
+      keys.name(Action::Forfeit)
+    ),
+    None => String::new(),
+  };
+
+  vec![line]
+}
+
+// Lays out the round as the server last described it.  The word given is
+// highlighted, such as one that was already found.  The status and word box are
+// shared rather than borrowed, since a new round brings a new word box.
+fn round_view(
+  state: &NetState,
+  me: &str,
+  hl: Option<&str>,
+  status: &el::ElemWrapper<TextView>,
+  word_box: &el::ElemWrapper<WordBox>,
+  button_bar: &el::ElemWrapper<ButtonBar>,
+  pairs: &Pairs,
+) -> el::ElemRef<'static> {
+  let boxes: Vec<_> = state
+    .words
+    .iter()
+    .map(|w| {
+      let form = WordlistForm {
+        blanked: w.blanked.clone(),
+        full: w.full.clone().unwrap_or_else(|| w.blanked.clone()),
+      };
+
+      let mut b = MatchBox::new(form, pairs.reveal, pairs.hl, pairs.hint);
+
+      b.set_revealed(w.full.is_some());
+      b.set_finder_pair(w.player.as_ref().and_then(|p| pairs.player(state, p)));
+
+      if w.word.is_some() && w.word.as_ref().map(|w| &**w) == hl {
+        b.set_style(MatchBoxStyle::Highlight);
+      } else if w.full.is_some() && w.player.is_none() {
+        b.set_style(MatchBoxStyle::Reveal); // Missed by everyone
+      }
+
+      el::wrap(b) as el::ElemRef
+    })
+    .collect();
+
+  let panel = el::wrap(ScrollView::new(
+    el::wrap(WrapBox::new(
+      boxes,
+      WrapMode::Cols,
+      WrapAlign::Begin,
+      WrapAlign::Middle,
+      0,
+      3,
+    )),
+    ScrollAxis::Horizontal,
+  ));
+
+  let score_box = el::wrap(ScoreBox::new(state.words.len(), None, pairs.best));
+
+  if let Some(p) = state.players.iter().find(|p| p.name == me) {
+    let found = state.words.iter().filter(|w| w.player.is_some()).count();
+
+    score_box.borrow_mut().set_score(p.points, found);
+  }
+
+  let bonus_box = el::wrap(BonusBox::new(pairs.hl));
+
+  {
+    let mut bonus_box = bonus_box.borrow_mut();
+
+    for b in &state.bonus {
+      bonus_box.push(&b.word, pairs.player(state, &b.player));
+    }
+
+    bonus_box.set_highlight(hl);
+  }
+
+  let scoreboard = el::wrap(Scoreboard::new(
+    state
+      .players
+      .iter()
+      .filter_map(|p| pairs.player(state, &p.name))
+      .collect(),
+  ));
+
+  {
+    let mut scoreboard = scoreboard.borrow_mut();
+
+    scoreboard.set_players(
+      state
+        .players
+        .iter()
+        .map(|p| (p.name.clone(), p.points, p.found))
+        .collect(),
+    );
+
+    if state.over {
+      let best = state.players.iter().map(|p| p.points).max().unwrap_or(0);
+
+      scoreboard.set_winners(
+        (0..state.players.len())
+          .filter(|i| state.players[*i].points == best)
+          .collect(),
+      );
+    } else if let Some(i) = state.players.iter().position(|p| p.name == me) {
+      scoreboard.set_turn(i); // Marks which player is you
+    }
+  }
+
+  let test_view = el::wrap(TestView::new(
+    word_box.clone() as el::ElemRef,
+    panel,
+    score_box,
+    None,
+    bonus_box,
+    None,
+    Some(scoreboard),
+    Some(button_bar.clone() as el::ElemRef),
+  ));
+
+  el::wrap(Grid::new(
+    vec![
+      (status.clone() as el::ElemRef, (0, 0)),
+      (test_view as el::ElemRef, (1, 0)),
+    ],
+    vec![GridLength::Content, GridLength::Dynamic(1.0)],
+    vec![GridLength::Dynamic(1.0)],
+  ))
+}
+
+fn new_word_box(letters: &str) -> el::ElemWrapper<WordBox> {
+  el::wrap(WordBox::new(letters.into(), 1, 2, 3))
+}
+
+pub fn run(
+  addr: &str,
+  name: &str,
+  colors: &ColorScheme,
+  keymap: &Keymap,
+) -> Result<()> {
+  let stream = TcpStream::connect(addr)?;
+  let (tx, messages) = channel();
+
+  {
+    let stream = stream.try_clone()?;
+
+    thread::spawn(move || read_server(stream, tx));
+  }
+
+  send(&stream, &ClientMessage::Join { name: name.into() })?;
+
+  // Nothing is drawn until the server has let us in
+  let mut state = match messages.recv() {
+    Ok(Some(ServerMessage::State { state })) => state,
+    Ok(Some(ServerMessage::Error { message })) => {
+      return Err(ErrorKind::Server(message).into())
+    }
+    _ => return Err(ErrorKind::Disconnected.into()),
+  };
+
+  let screen = Screen::init();
+  let win = screen.win();
+
+  let keys = keymap.resolve().only(ONLINE_ACTIONS);
+
+  // These match the pairs used by a normal round
+  nc::init_pair(1, colors.ghost.0, colors.ghost.1);
+  nc::init_pair(2, colors.bad_ghost.0, colors.bad_ghost.1);
+  nc::init_pair(3, colors.auto_ghost.0, colors.auto_ghost.1);
+  nc::init_pair(4, colors.reveal.0, colors.reveal.1);
+  nc::init_pair(5, colors.hl.0, colors.hl.1);
+  nc::init_pair(6, colors.best.0, colors.best.1);
+  nc::init_pair(8, colors.hint.0, colors.hint.1);
+
+  for (i, &(fg, bg)) in colors.players.iter().enumerate() {
+    nc::init_pair(9 + i as i16, fg, bg);
+  }
+
+  let pairs = Pairs {
+    reveal: 4,
+    hl: 5,
+    hint: 8,
+    best: 6,
+    players: (0..colors.players.len()).map(|i| 9 + i as i32).collect(),
+  };
+
+  let status_view = el::wrap(TextView::new(status(&state, None, &keys)));
+  let mut word_box = new_word_box(&state.letters);
+  let mut hl: Option<String> = None;
+  let mut history = History::new();
+
+  let button_bar = el::wrap(ButtonBar::new(
+    BUTTONS.iter().map(|&(label, a)| (label.into(), a)).collect(),
+  ));
+
+  let mut ui_root = UiRoot::new(
+    win,
+    round_view(
+      &state,
+      name,
+      None,
+      &status_view,
+      &word_box,
+      &button_bar,
+      &pairs,
+    ),
+  );
+
+  ui_root.resize();
+
+  nc::wtimeout(win, TICK_MILLIS);
+
+  let ret = 'main: loop {
+    // Set whenever the round has to be laid out again
+    let mut relayout = false;
+    let mut message: Option<String> = None;
+
+    loop {
+      let msg = match messages.try_recv() {
+        Ok(Some(m)) => m,
+        Ok(None) | Err(TryRecvError::Disconnected) => {
+          break 'main Err(ErrorKind::Disconnected.into());
+        }
+        Err(TryRecvError::Empty) => break,
+      };
+
+      match msg {
+        ServerMessage::State { state: s } => {
+          if s.round != state.round {
+            word_box = new_word_box(&s.letters);
+            hl = None;
+          }
+
+          state = s;
+          relayout = true;
+        }
+        ServerMessage::Guess { word, result, .. } => match &*result {
+          "wrong" => word_box.borrow_mut().set_bad(true),
+          "already_found" | "already_bonus" => {
+            message = Some(format!("{} was already found", word));
+            hl = Some(word);
+            relayout = true;
+          }
+          _ => {}
+        },
+        ServerMessage::Claim {
+          player,
+          word,
+          points,
+        } => {
+          message = Some(format!("{} found {} (+{})", player, word, points));
+          hl = None;
+        }
+        ServerMessage::Error { message: m } => message = Some(m),
+      }
+    }
+
+    if relayout {
+      let hl = hl.as_ref().map(|h| &**h);
+
+      status_view.borrow_mut().set_lines(status(
+        &state,
+        message.as_ref().map(|m| &**m),
+        &keys,
+      ));
+      ui_root.set_child(round_view(
+        &state,
+        name,
+        hl,
+        &status_view,
+        &word_box,
+        &button_bar,
+        &pairs,
+      ));
+      ui_root.resize();
+    } else if let Some(m) = message {
+      status_view
+        .borrow_mut()
+        .set_lines(status(&state, Some(&m), &keys));
+    }
+
+    // Timing out waiting for a key comes back as no action at all, so that
+    // the server gets checked for news
+    let action = ui_root.run(win, &keys, |e| match e {
+      Event::Action(a) => Some(Some(a)),
+      Event::Timeout => Some(None),
+      _ => None,
+    });
+
+    match action {
+      Some(Action::Quit) => {
+        send(&stream, &ClientMessage::Quit).ok();
+        break Ok(());
+      }
+      Some(Action::Shuffle) => {
+        word_box.borrow_mut().shuffle(|letters| {
+          let mut chars: Vec<char> = letters.chars().collect();
+
+          rand::thread_rng().shuffle(&mut chars);
+          chars.into_iter().collect()
+        });
+      }
+      Some(Action::Forfeit) => {
+        if !state.over {
+          send(&stream, &ClientMessage::GiveUp)?;
+        }
+      }
+      Some(Action::Submit) => {
+        if state.over {
+          send(&stream, &ClientMessage::NewRound)?;
+        } else {
+          let mut word_box = word_box.borrow_mut();
+          let buf = word_box.buf().clone();
+
+          if !buf.is_empty() {
+            history.push(&buf);
+            send(&stream, &ClientMessage::Guess { word: buf })?;
+          }
+
+          word_box.clear();
+        }
+      }
+      Some(Action::HistoryNext) => {
+        if let Some(s) = history.next() {
+          word_box.borrow_mut().set_buf(s);
+        }
+      }
+      Some(Action::HistoryPrev) => {
+        let mut word_box = word_box.borrow_mut();
+
+        if let Some(s) = history.prev(word_box.buf()) {
+          word_box.set_buf(s);
+        }
+      }
+      _ => {}
+    }
+  };
+
+  ret
+}
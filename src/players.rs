@@ -0,0 +1,146 @@
+// Hot-seat play, where several people share one terminal and take turns
+// guessing.  The round itself is still scored as a whole; this only keeps track
+// of who found what.
+
+use score::HINT_PENALTY;
+use std::{collections::HashMap, str::FromStr};
+
+pub static MIN_PLAYERS: usize = 2;
+pub static MAX_PLAYERS: usize = 4;
+
+// A comma-separated list of player names, as given on the command line
+#[derive(Clone)]
+pub struct PlayerNames(pub Vec<String>);
+
+impl FromStr for PlayerNames {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, String> {
+    let names: Vec<String> = s
+      .split(',')
+      .map(|n| n.trim().to_string())
+      .filter(|n| !n.is_empty())
+      .collect();
+
+    if names.len() < MIN_PLAYERS || names.len() > MAX_PLAYERS {
+      return Err(format!(
+        "expected {} to {} player names, got {}",
+        MIN_PLAYERS,
+        MAX_PLAYERS,
+        names.len()
+      ));
+    }
+
+    for (i, name) in names.iter().enumerate() {
+      if names[..i].contains(name) {
+        return Err(format!("player name '{}' given twice", name));
+      }
+    }
+
+    Ok(PlayerNames(names))
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Player {
+  pub name: String,
+  pub points: u32,
+  #[serde(default)]
+  pub penalty: u32, // Taken off points at the end, like a RoundScore's
+  pub words: Vec<String>, // Set and bonus words, in the order they were found
+}
+
+impl Player {
+  pub fn score(&self) -> u32 {
+    self.points.saturating_sub(self.penalty)
+  }
+}
+
+pub struct Players {
+  players: Vec<Player>,
+  turn: usize,
+  finders: HashMap<String, usize>, // Player index, by word
+}
+
+impl Players {
+  pub fn new(names: &PlayerNames) -> Self {
+    Self::resume(
+      names
+        .0
+        .iter()
+        .map(|n| Player {
+          name: n.clone(),
+          points: 0,
+          penalty: 0,
+          words: Vec::new(),
+        })
+        .collect(),
+      0,
+    )
+  }
+
+  pub fn resume(players: Vec<Player>, turn: usize) -> Self {
+    let mut finders = HashMap::new();
+
+    for (i, player) in players.iter().enumerate() {
+      for word in &player.words {
+        finders.insert(word.clone(), i);
+      }
+    }
+
+    Self {
+      turn: turn % players.len().max(1),
+      players,
+      finders,
+    }
+  }
+
+  pub fn list(&self) -> &[Player] {
+    &self.players
+  }
+
+  pub fn turn(&self) -> usize {
+    self.turn
+  }
+
+  // Whoever found the word, if anyone has
+  pub fn finder(&self, word: &str) -> Option<usize> {
+    self.finders.get(word).cloned()
+  }
+
+  // Credits a newly found word to the player whose turn it is
+  pub fn credit(&mut self, word: &str, points: u32) {
+    let player = &mut self.players[self.turn];
+
+    player.points = player.points + points;
+    player.words.push(word.to_string());
+    self.finders.insert(word.to_string(), self.turn);
+  }
+
+  // Points that don't come with a word, like the bonus for clearing early
+  pub fn award(&mut self, points: u32) {
+    let player = &mut self.players[self.turn];
+
+    player.points = player.points + points;
+  }
+
+  // Hints cost whoever asks for them
+  pub fn add_hint(&mut self) {
+    let player = &mut self.players[self.turn];
+
+    player.penalty = player.penalty + HINT_PENALTY;
+  }
+
+  pub fn pass_turn(&mut self) {
+    self.turn = (self.turn + 1) % self.players.len();
+  }
+
+  // Everyone tied for the most points
+  pub fn leaders(&self) -> Vec<usize> {
+    let best = self.players.iter().map(|p| p.score()).max().unwrap_or(0);
+
+    (0..self.players.len())
+      .filter(|i| self.players[*i].score() == best)
+      .collect()
+  }
+}
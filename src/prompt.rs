@@ -0,0 +1,56 @@
+// Questions asked on the terminal before curses takes it over
+
+use save::SavedRound;
+use std::io::{self, prelude::*};
+
+pub fn resume(saved: &SavedRound) -> bool {
+  let with = if saved.players.is_empty() {
+    String::new()
+  } else {
+    let names: Vec<_> = saved.players.iter().map(|p| &*p.name).collect();
+
+    format!(" with {}", names.join(", "))
+  };
+
+  write!(
+    io::stderr(),
+    "resume saved round ({} letters, found {}/{}{})? [Y/n] ",
+    saved.key_len(),
+    saved.score.found(),
+    saved.score.total(),
+    with
+  ).unwrap();
+  io::stderr().flush().unwrap();
+
+  let mut answer = String::new();
+
+  if io::stdin().read_line(&mut answer).unwrap() == 0 {
+    writeln!(io::stderr(), "").unwrap();
+    return false;
+  }
+
+  match &*answer.trim().to_lowercase() {
+    "" | "y" | "yes" => true,
+    _ => false,
+  }
+}
+
+// Asks for a word length, returning None at the end of input
+pub fn word_len() -> Option<usize> {
+  loop {
+    let mut len_str = String::new();
+
+    write!(io::stderr(), "word length: ").unwrap();
+    io::stderr().flush().unwrap();
+
+    if io::stdin().read_line(&mut len_str).unwrap() == 0 {
+      writeln!(io::stderr(), "").unwrap();
+      return None;
+    }
+
+    match len_str.trim().parse() {
+      Ok(l) => return Some(l),
+      Err(e) => writeln!(io::stderr(), "invalid number: {}", e).unwrap(),
+    }
+  }
+}
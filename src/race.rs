@@ -0,0 +1,461 @@
+// A race between players connected over the network, as run by
+// omelette-server.  Everyone plays the same round at once, and each word goes
+// to whoever claims it first.
+
+use difficulty::Difficulty;
+use game::{GuessOutcome, Round};
+use net::{
+  ClientMessage, NetBonus, NetPlayer, NetState, NetWord, ServerMessage,
+};
+use players::Player;
+use rand::Rng;
+use seed::GameRng;
+use serde_json;
+use std::{
+  collections::{BTreeSet, HashMap},
+  io::{prelude::*, BufReader},
+  net::{Shutdown, TcpListener, TcpStream},
+  sync::mpsc::{channel, Sender},
+  thread,
+};
+use word_list::{WordList, WordlistForm};
+
+static MAX_NAME_LEN: usize = 16;
+
+// Things worth telling whoever runs the server about
+pub enum Notice {
+  Round {
+    round: u64,
+    letters: usize,
+    words: usize,
+  },
+  Joined(String),
+  Left(String),
+}
+
+// Everything the client threads report back to the game
+enum Event {
+  Connect(usize, TcpStream),
+  Line(usize, String),
+  Disconnect(usize),
+}
+
+struct Client {
+  stream: TcpStream,
+  player: Option<usize>, // Set once the client has joined
+}
+
+struct Game<'a> {
+  words: &'a WordList,
+  rng: GameRng,
+  lens: Vec<usize>,
+  difficulty: Difficulty,
+  round: Round<'a>,
+  round_no: u64,
+  forms: Vec<(String, WordlistForm)>, // Every form of every word in the set
+  finders: HashMap<String, usize>,    // Player index, by word
+  players: Vec<Player>,               // Everyone who has joined, even if gone
+  clients: HashMap<usize, Client>,
+  give_up: BTreeSet<usize>, // Players who have voted to give up
+  notify: Box<FnMut(Notice) + 'a>,
+}
+
+impl<'a> Game<'a> {
+  fn new(
+    words: &'a WordList,
+    mut rng: GameRng,
+    lens: Vec<usize>,
+    difficulty: Difficulty,
+    notify: Box<FnMut(Notice) + 'a>,
+  ) -> Self {
+    let round = Self::random_round(words, &mut rng, &lens, difficulty);
+
+    let mut ret = Self {
+      words,
+      rng,
+      lens,
+      difficulty,
+      round,
+      round_no: 1,
+      forms: Vec::new(),
+      finders: HashMap::new(),
+      players: Vec::new(),
+      clients: HashMap::new(),
+      give_up: BTreeSet::new(),
+      notify,
+    };
+
+    ret.start_round();
+    ret
+  }
+
+  // The lengths have already been checked for words, so this always succeeds
+  // eventually
+  fn random_round(
+    words: &'a WordList,
+    rng: &mut GameRng,
+    lens: &[usize],
+    difficulty: Difficulty,
+  ) -> Round<'a> {
+    loop {
+      let len = lens[rng.gen_range(0, lens.len())];
+
+      if let Some(r) = Round::random(words, len, rng.gen(), difficulty) {
+        return r;
+      }
+    }
+  }
+
+  fn new_round(&mut self) {
+    self.round = Self::random_round(
+      self.words,
+      &mut self.rng,
+      &self.lens,
+      self.difficulty,
+    );
+    self.round_no = self.round_no + 1;
+
+    for player in &mut self.players {
+      player.points = 0;
+      player.words.clear();
+    }
+
+    self.start_round();
+  }
+
+  fn start_round(&mut self) {
+    self.round.shuffle(); // The letters start out as the key itself
+
+    let words = self.words;
+
+    self.forms = self
+      .round
+      .set()
+      .iter()
+      .flat_map(|n| words.get_form(n).into_iter().map(move |f| (n.clone(), f)))
+      .collect();
+
+    self.finders.clear();
+    self.give_up.clear();
+
+    (self.notify)(Notice::Round {
+      round: self.round_no,
+      letters: self.round.key_len(),
+      words: self.round.set().len(),
+    });
+  }
+
+  fn connected(&self) -> BTreeSet<usize> {
+    self.clients.values().filter_map(|c| c.player).collect()
+  }
+
+  fn state(&self) -> NetState {
+    let over = self.round.is_over();
+    let name = |i: &usize| self.players[*i].name.clone();
+
+    NetState {
+      round: self.round_no,
+      letters: self.round.letters().into(),
+      words: self
+        .forms
+        .iter()
+        .map(|&(ref norm, ref form)| {
+          let shown = over || self.round.is_found(norm);
+
+          NetWord {
+            blanked: form.blanked.clone(),
+            word: if shown { Some(norm.clone()) } else { None },
+            full: if shown { Some(form.full.clone()) } else { None },
+            player: self.finders.get(norm).map(&name),
+          }
+        })
+        .collect(),
+      bonus: self
+        .round
+        .bonus_words()
+        .iter()
+        .map(|w| NetBonus {
+          word: w.clone(),
+          player: self.finders.get(w).map_or(String::new(), &name),
+        })
+        .collect(),
+      players: {
+        let connected = self.connected();
+
+        self
+          .players
+          .iter()
+          .enumerate()
+          .map(|(i, p)| NetPlayer {
+            name: p.name.clone(),
+            points: p.points,
+            found: p.words.len(),
+            connected: connected.contains(&i),
+          })
+          .collect()
+      },
+      give_up: self.give_up.iter().map(&name).collect(),
+      over,
+    }
+  }
+
+  // Write errors are left for the client's thread to notice
+  fn send(&self, id: usize, msg: &ServerMessage) {
+    if let Some(client) = self.clients.get(&id) {
+      let mut stream = &client.stream;
+
+      writeln!(stream, "{}", serde_json::to_string(msg).unwrap()).ok();
+    }
+  }
+
+  fn broadcast(&self, msg: &ServerMessage) {
+    for (id, client) in &self.clients {
+      if client.player.is_some() {
+        self.send(*id, msg);
+      }
+    }
+  }
+
+  fn broadcast_state(&self) {
+    self.broadcast(&ServerMessage::State {
+      state: self.state(),
+    });
+  }
+
+  fn error(&self, id: usize, message: &str) {
+    self.send(
+      id,
+      &ServerMessage::Error {
+        message: message.into(),
+      },
+    );
+  }
+
+  // Gives up on the round once everyone still here has voted to
+  fn check_give_up(&mut self) {
+    let connected = self.connected();
+
+    if !self.round.is_over()
+      && !connected.is_empty()
+      && connected.is_subset(&self.give_up)
+    {
+      self.round.forfeit();
+    }
+  }
+
+  fn handle(&mut self, event: Event) {
+    match event {
+      Event::Connect(id, stream) => {
+        self.clients.insert(
+          id,
+          Client {
+            stream,
+            player: None,
+          },
+        );
+      }
+      Event::Line(id, line) => {
+        if line.trim().is_empty() {
+          return;
+        }
+
+        match serde_json::from_str(&line) {
+          Ok(msg) => self.handle_message(id, msg),
+          Err(e) => self.error(id, &format!("invalid command: {}", e)),
+        }
+      }
+      Event::Disconnect(id) => self.disconnect(id),
+    }
+  }
+
+  fn disconnect(&mut self, id: usize) {
+    let client = match self.clients.remove(&id) {
+      Some(c) => c,
+      None => return,
+    };
+
+    client.stream.shutdown(Shutdown::Both).ok();
+
+    if let Some(player) = client.player {
+      let name = self.players[player].name.clone();
+
+      (self.notify)(Notice::Left(name));
+
+      self.give_up.remove(&player);
+      self.check_give_up();
+      self.broadcast_state();
+    }
+  }
+
+  fn handle_message(&mut self, id: usize, msg: ClientMessage) {
+    let player = match self.clients[&id].player {
+      Some(p) => p,
+      None => {
+        return match msg {
+          ClientMessage::Join { name } => self.join(id, &name),
+          ClientMessage::Quit => self.disconnect(id),
+          _ => self.error(id, "join first"),
+        }
+      }
+    };
+
+    match msg {
+      ClientMessage::Join { .. } => self.error(id, "already joined"),
+      ClientMessage::Quit => self.disconnect(id),
+      ClientMessage::Guess { word } => {
+        if self.round.is_over() {
+          return self.error(id, "the round is over");
+        }
+
+        let word = word.trim().to_lowercase();
+        let outcome = self.round.guess(&word);
+        let (result, points) = outcome.result();
+
+        self.send(
+          id,
+          &ServerMessage::Guess {
+            word: word.clone(),
+            result: result.into(),
+            points,
+          },
+        );
+
+        match outcome {
+          GuessOutcome::Found(_) | GuessOutcome::Bonus(_) => {
+            {
+              let p = &mut self.players[player];
+
+              p.points = p.points + points;
+              p.words.push(word.clone());
+            }
+
+            self.finders.insert(word.clone(), player);
+
+            self.broadcast(&ServerMessage::Claim {
+              player: self.players[player].name.clone(),
+              word,
+              points,
+            });
+            self.broadcast_state();
+          }
+          _ => {}
+        }
+      }
+      ClientMessage::GiveUp => {
+        if self.round.is_over() {
+          return self.error(id, "the round is over");
+        }
+
+        self.give_up.insert(player);
+        self.check_give_up();
+        self.broadcast_state();
+      }
+      ClientMessage::NewRound => {
+        if !self.round.is_over() {
+          return self.error(id, "the round isn't over yet");
+        }
+
+        self.new_round();
+        self.broadcast_state();
+      }
+      ClientMessage::State => self.send(
+        id,
+        &ServerMessage::State {
+          state: self.state(),
+        },
+      ),
+    }
+  }
+
+  // Someone coming back under the same name picks up where they left off
+  fn join(&mut self, id: usize, name: &str) {
+    let name = name.trim();
+
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+      return self.error(
+        id,
+        &format!("names must be 1 to {} characters long", MAX_NAME_LEN),
+      );
+    }
+
+    let player = match self.players.iter().position(|p| p.name == name) {
+      Some(i) if self.connected().contains(&i) => {
+        return self.error(id, &format!("{} is already playing", name))
+      }
+      Some(i) => i,
+      None => {
+        self.players.push(Player {
+          name: name.into(),
+          points: 0,
+          penalty: 0,
+          words: Vec::new(),
+        });
+
+        self.players.len() - 1
+      }
+    };
+
+    (self.notify)(Notice::Joined(name.into()));
+
+    self.clients.get_mut(&id).unwrap().player = Some(player);
+    self.broadcast_state();
+  }
+}
+
+// Reads lines from a client until it hangs up
+fn read_client(id: usize, stream: TcpStream, events: Sender<Event>) {
+  for line in BufReader::new(stream).lines() {
+    match line {
+      Ok(l) => {
+        if events.send(Event::Line(id, l)).is_err() {
+          return;
+        }
+      }
+      Err(_) => break,
+    }
+  }
+
+  events.send(Event::Disconnect(id)).ok();
+}
+
+// Runs races for whoever connects to the listener, picking rounds from the
+// given lengths (each of which has to have words), and passes anything of
+// note to notify.  This never returns.
+pub fn serve<F: FnMut(Notice)>(
+  listener: TcpListener,
+  words: &WordList,
+  rng: GameRng,
+  lens: Vec<usize>,
+  difficulty: Difficulty,
+  notify: F,
+) {
+  let (events, rx) = channel();
+
+  thread::spawn(move || {
+    for (id, stream) in listener.incoming().enumerate() {
+      let stream = match stream {
+        Ok(s) => s,
+        Err(_) => continue,
+      };
+
+      let reader = match stream.try_clone() {
+        Ok(s) => s,
+        Err(_) => continue,
+      };
+
+      if events.send(Event::Connect(id, stream)).is_err() {
+        return;
+      }
+
+      let events = events.clone();
+
+      thread::spawn(move || read_client(id, reader, events));
+    }
+  });
+
+  let mut game = Game::new(words, rng, lens, difficulty, Box::new(notify));
+
+  for event in rx {
+    game.handle(event);
+  }
+}
@@ -0,0 +1,218 @@
+use difficulty::Difficulty;
+use game::{Progress, Round};
+use history::History;
+use mode::GameMode;
+use players::{Player, Players};
+use score::RoundScore;
+use serde_json;
+use std::{
+  collections::BTreeMap,
+  fs::{self, File},
+  io::{self, BufReader, BufWriter},
+  path::Path,
+  time::Duration,
+};
+use word_list::WordList;
+
+// Bump this whenever a change to SavedRound can't be handled by defaults, so
+// older versions of the game refuse newer saves instead of misreading them
+pub static SAVE_VERSION: u32 = 1;
+
+// A round that was quit partway through.  Any field missing from a save is
+// given its default, so that saves from older versions still load.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedRound {
+  pub version: u32,
+  pub round_seed: u64,
+  pub mode: String,
+  pub time_limit: Option<u64>, // In seconds, for blitz rounds
+  pub difficulty: Option<String>,
+  pub key: String,
+  pub found: Vec<String>,
+  pub hints: BTreeMap<String, usize>, // Letters hinted, by word
+  pub bonus: Vec<String>,
+  pub buf: String,
+  pub history: Vec<String>,
+  pub players: Vec<Player>, // Empty unless this was a hot-seat round
+  pub turn: usize,
+  pub score: RoundScore,
+  pub elapsed_ms: u64,
+}
+
+impl SavedRound {
+  pub fn new(
+    round: &Round,
+    mode: GameMode,
+    buf: &str,
+    history: &History,
+    players: Option<&Players>,
+    elapsed: Duration,
+  ) -> Self {
+    Self {
+      version: SAVE_VERSION,
+      round_seed: round.seed(),
+      mode: mode.name().into(),
+      time_limit: mode.time_limit().map(|l| l.as_secs()),
+      difficulty: Some(round.difficulty().name().into()),
+      key: round.key().into(),
+      found: round.found().iter().cloned().collect(),
+      hints: round.hints().clone(),
+      bonus: round.bonus_words().to_vec(),
+      buf: buf.into(),
+      history: history.entries().to_vec(),
+      players: players.map_or(Vec::new(), |p| p.list().to_vec()),
+      turn: players.map_or(0, |p| p.turn()),
+      score: round.score().clone(),
+      elapsed_ms: duration_ms(elapsed),
+    }
+  }
+
+  // Returns None if there's no save, or if it's from a newer version
+  pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Option<Self>> {
+    let file = match File::open(path) {
+      Ok(f) => f,
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+      Err(e) => return Err(e),
+    };
+
+    let saved: Self = serde_json::from_reader(BufReader::new(file))
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    if saved.version > SAVE_VERSION {
+      return Ok(None);
+    }
+
+    Ok(Some(saved))
+  }
+
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+
+    serde_json::to_writer_pretty(file, self)
+      .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+  }
+
+  pub fn remove<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    match fs::remove_file(path) {
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+      r => r,
+    }
+  }
+
+  // Picks the round back up where it was quit
+  pub fn resume<'a>(&self, words: &'a WordList) -> Round<'a> {
+    Round::resume(
+      words,
+      self.round_seed,
+      self.difficulty(),
+      self.key.clone(),
+      Progress {
+        found: self.found.iter().cloned().collect(),
+        hints: self.hints.clone(),
+        bonus: self.bonus.clone(),
+        score: self.score.clone(),
+      },
+    )
+  }
+
+  // The length of the saved round, in letters
+  pub fn key_len(&self) -> usize {
+    self.key.chars().count()
+  }
+
+  pub fn game_mode(&self) -> GameMode {
+    match (
+      self.mode.parse().unwrap_or(GameMode::Normal),
+      self.time_limit,
+    ) {
+      (GameMode::Blitz(_), Some(t)) => GameMode::Blitz(Duration::from_secs(t)),
+      (m, _) => m,
+    }
+  }
+
+  // Rounds saved before difficulties existed had every word in the set, which
+  // is what hard gives
+  pub fn difficulty(&self) -> Difficulty {
+    match self.difficulty {
+      Some(ref d) => d.parse().unwrap_or_default(),
+      None => Difficulty::Hard,
+    }
+  }
+
+  pub fn players(&self) -> Option<Players> {
+    if self.players.is_empty() {
+      None
+    } else {
+      Some(Players::resume(self.players.clone(), self.turn))
+    }
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    Duration::from_millis(self.elapsed_ms)
+  }
+}
+
+pub fn duration_ms(d: Duration) -> u64 {
+  d.as_secs() * 1000 + d.subsec_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::{env, path::PathBuf};
+
+  fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+      .join("tests")
+      .join("fixtures")
+      .join(name)
+  }
+
+  // Saves from before difficulties and hot-seat play are missing those fields
+  #[test]
+  fn loads_v1_save() {
+    let saved = SavedRound::load(fixture("save-v1.json")).unwrap().unwrap();
+
+    assert_eq!(saved.key, "tones");
+    assert_eq!(saved.found, vec!["notes", "stone"]);
+    assert_eq!(saved.hints.get("onset"), Some(&2));
+    assert_eq!(saved.bonus, vec!["ton"]);
+    assert_eq!(saved.buf, "ton");
+    assert_eq!(saved.history.len(), 3);
+    assert_eq!(saved.elapsed(), Duration::from_millis(42500));
+
+    assert!(saved.game_mode() == GameMode::Blitz(Duration::from_secs(90)));
+    assert!(saved.difficulty() == Difficulty::Hard);
+    assert!(saved.players().is_none());
+
+    assert_eq!(saved.score.points(), 545);
+    assert_eq!(saved.score.found(), 2);
+    assert_eq!(saved.score.total(), 5);
+    assert_eq!(saved.score.hints(), 1);
+  }
+
+  #[test]
+  fn multi_byte_key_len() {
+    let saved = SavedRound {
+      key: "éte".into(),
+      ..SavedRound::default()
+    };
+
+    assert_eq!(saved.key_len(), 3);
+  }
+
+  #[test]
+  fn skips_newer_save() {
+    let path = env::temp_dir().join("omelette-test-newer-save.json");
+    let mut saved = SavedRound::load(fixture("save-v1.json")).unwrap().unwrap();
+
+    saved.version = SAVE_VERSION + 1;
+    saved.save(&path).unwrap();
+
+    let loaded = SavedRound::load(&path).unwrap();
+    SavedRound::remove(&path).unwrap();
+
+    assert!(loaded.is_none());
+  }
+}
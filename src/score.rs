@@ -0,0 +1,224 @@
+use serde_json;
+use std::{
+  collections::BTreeMap,
+  env,
+  fs::File,
+  io::{self, BufReader, BufWriter},
+  path::Path,
+  time::Duration,
+};
+use mode::GameMode;
+
+static KEY_WORD_BONUS: u32 = 500;
+static CLEAR_BONUS: u32 = 1000;
+static TIME_BONUS_PER_SEC: u32 = 10;
+pub static HINT_PENALTY: u32 = 50;
+static MAX_HIGH_SCORES: usize = 10;
+
+pub fn player_name() -> String {
+  env::var("USER").unwrap_or_else(|_| "player".into())
+}
+
+pub fn word_points(len: usize) -> u32 {
+  // Longer words are worth disproportionately more, like the original
+  (len * len * 10) as u32
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoundScore {
+  points: u32,
+  found: usize,
+  total: usize,
+  bonus: usize,
+  hints: usize,
+  // Taken off at the end rather than as hints are used, so that a hint still
+  // costs something when there are no points yet to take it from
+  penalty: u32,
+  key_len: usize,
+}
+
+impl RoundScore {
+  pub fn new(key_len: usize, total: usize) -> Self {
+    Self {
+      points: 0,
+      found: 0,
+      total,
+      bonus: 0,
+      hints: 0,
+      penalty: 0,
+      key_len,
+    }
+  }
+
+  pub fn points(&self) -> u32 {
+    self.points.saturating_sub(self.penalty)
+  }
+
+  pub fn found(&self) -> usize {
+    self.found
+  }
+
+  pub fn total(&self) -> usize {
+    self.total
+  }
+
+  pub fn bonus(&self) -> usize {
+    self.bonus
+  }
+
+  pub fn hints(&self) -> usize {
+    self.hints
+  }
+
+  pub fn cleared(&self) -> bool {
+    self.found >= self.total
+  }
+
+  // Returns the number of points awarded for the word
+  pub fn add_word(&mut self, len: usize) -> u32 {
+    let mut points = word_points(len);
+
+    if len == self.key_len {
+      points = points + KEY_WORD_BONUS;
+    }
+
+    self.found = self.found + 1;
+    self.points = self.points + points;
+
+    if self.cleared() {
+      self.points = self.points + CLEAR_BONUS;
+      points = points + CLEAR_BONUS;
+    }
+
+    points
+  }
+
+  // Bonus words are worth half as much, and don't count towards clearing
+  pub fn add_bonus_word(&mut self, len: usize) -> u32 {
+    let points = word_points(len) / 2;
+
+    self.bonus = self.bonus + 1;
+    self.points = self.points + points;
+
+    points
+  }
+
+  pub fn add_hint(&mut self) {
+    self.hints = self.hints + 1;
+    self.penalty = self.penalty + HINT_PENALTY;
+  }
+
+  // Awarded for clearing a timed round early
+  pub fn add_time_bonus(&mut self, left: Duration) -> u32 {
+    let points = left.as_secs() as u32 * TIME_BONUS_PER_SEC;
+
+    self.points = self.points + points;
+
+    points
+  }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+  pub name: String,
+  pub points: u32,
+  pub key: String,
+  pub found: usize,
+  pub total: usize,
+  #[serde(default)]
+  pub bonus: usize,
+  #[serde(default)]
+  pub hints: usize,
+}
+
+impl HighScore {
+  pub fn new(key: &str, score: &RoundScore) -> Self {
+    Self {
+      name: player_name(),
+      points: score.points(),
+      key: key.into(),
+      found: score.found(),
+      total: score.total(),
+      bonus: score.bonus(),
+      hints: score.hints(),
+    }
+  }
+}
+
+type HighScoreTables = BTreeMap<usize, Vec<HighScore>>; // Keyed by word length
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct HighScores {
+  tables: HighScoreTables,
+  #[serde(default)]
+  blitz: HighScoreTables,
+}
+
+impl HighScores {
+  pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+    let file = match File::open(path) {
+      Ok(f) => f,
+      Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+        return Ok(Default::default())
+      }
+      Err(e) => return Err(e),
+    };
+
+    serde_json::from_reader(BufReader::new(file))
+      .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+  }
+
+  pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+
+    serde_json::to_writer_pretty(file, self)
+      .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+  }
+
+  fn tables(&self, mode: GameMode) -> &HighScoreTables {
+    match mode {
+      GameMode::Normal => &self.tables,
+      GameMode::Blitz(_) => &self.blitz,
+    }
+  }
+
+  fn tables_mut(&mut self, mode: GameMode) -> &mut HighScoreTables {
+    match mode {
+      GameMode::Normal => &mut self.tables,
+      GameMode::Blitz(_) => &mut self.blitz,
+    }
+  }
+
+  pub fn table(&self, mode: GameMode, len: usize) -> &[HighScore] {
+    self.tables(mode).get(&len).map_or(&[][..], |t| &t[..])
+  }
+
+  pub fn best(&self, mode: GameMode, len: usize) -> Option<&HighScore> {
+    self.table(mode, len).first()
+  }
+
+  // Returns the rank of the new entry, if it made the table
+  pub fn insert(
+    &mut self,
+    mode: GameMode,
+    len: usize,
+    score: HighScore,
+  ) -> Option<usize> {
+    let table = self.tables_mut(mode).entry(len).or_insert_with(Vec::new);
+
+    let rank = table
+      .iter()
+      .position(|s| s.points < score.points)
+      .unwrap_or(table.len());
+
+    if rank >= MAX_HIGH_SCORES {
+      return None;
+    }
+
+    table.insert(rank, score);
+    table.truncate(MAX_HIGH_SCORES);
+
+    Some(rank)
+  }
+}
@@ -0,0 +1,95 @@
+// Screens swapped in over a round for a while, putting the round back
+// afterwards
+
+use game::Analysis;
+use keymap::{Action, KeyCodes};
+use nc;
+use stats::Stats;
+use std::cmp;
+use tui::{controls::*, element as el, event::Event};
+use Result;
+
+// Swaps the stats screen in until a key is pressed
+pub fn show_stats(
+  win: nc::WINDOW,
+  ui_root: &mut UiRoot,
+  keys: &KeyCodes,
+  stats: &Stats,
+  player: &str,
+) -> Result<()> {
+  let mut lines = stats.summary(player)?.lines();
+  lines.push(String::new());
+  lines.push("press any key to return".into());
+
+  let prev = ui_root.set_child(el::wrap(TextView::new(lines)));
+  ui_root.resize();
+
+  nc::wtimeout(win, -1);
+  ui_root.run(win, keys, |_| Some(()));
+
+  ui_root.set_child(prev);
+  ui_root.resize();
+
+  Ok(())
+}
+
+// Swaps in every word of the round until a key other than the one for
+// ToggleBonus (which shows or hides the bonus words) is pressed.  Big sets run
+// off the bottom of the screen, so the list can be scrolled with the page keys
+// or the mouse wheel.
+pub fn show_analysis(
+  win: nc::WINDOW,
+  ui_root: &mut UiRoot,
+  keys: &KeyCodes,
+  title: &str,
+  analysis: &Analysis,
+) {
+  let (mut h, mut w) = (0, 0);
+  nc::getmaxyx(win, &mut h, &mut w);
+
+  // Leave a column for the scroll bar
+  let width = cmp::max(1, w - 1) as usize;
+
+  let lines = |show_bonus| {
+    let mut ret = vec![title.to_string(), String::new()];
+
+    ret.extend(analysis.lines(show_bonus, width));
+    ret.push(String::new());
+    ret.push(format!(
+      "found words are marked with *; {} shows bonus words",
+      keys.name(Action::ToggleBonus)
+    ));
+
+    ret
+  };
+
+  let mut show_bonus = false;
+  let view = el::wrap(TextView::new(lines(show_bonus)));
+  let prev = ui_root.set_child(el::wrap(ScrollView::new(
+    view.clone(),
+    ScrollAxis::Vertical,
+  )));
+  ui_root.resize();
+
+  nc::wtimeout(win, -1);
+
+  // Paging and scrolling are taken care of by the scroll view
+  loop {
+    let toggle = ui_root.run(win, keys, |e| match e {
+      Event::Action(Action::ToggleBonus) => Some(true),
+      Event::Scroll(..) => None,
+      _ => Some(false),
+    });
+
+    if !toggle {
+      break;
+    }
+
+    show_bonus = !show_bonus;
+    view.borrow_mut().set_lines(lines(show_bonus));
+    ui_root.resize();
+  }
+
+  ui_root.set_child(prev);
+  ui_root.resize();
+}
@@ -0,0 +1,28 @@
+use rand::{prng::ChaChaRng, SeedableRng};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// NB: this is a named algorithm rather than StdRng so that a given seed
+//     produces the same puzzles regardless of platform or rand version
+pub type GameRng = ChaChaRng;
+
+static SECS_PER_DAY: u64 = 60 * 60 * 24;
+
+pub fn rng_from_seed(seed: u64) -> GameRng {
+  let mut bytes = [0u8; 32];
+
+  for (i, b) in bytes.iter_mut().take(8).enumerate() {
+    *b = (seed >> (i * 8)) as u8;
+  }
+
+  GameRng::from_seed(bytes)
+}
+
+// The number of days since the Unix epoch (in UTC), so that everyone playing on
+// the same day gets the same seed
+pub fn daily_seed() -> u64 {
+  let now = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .expect("system clock is set before 1970");
+
+  now.as_secs() / SECS_PER_DAY
+}
@@ -0,0 +1,126 @@
+// A server for racing other players over the network.  Everyone connected
+// plays the same round at once, and each word goes to whoever claims it first.
+// Clients connect with `omelette join`; see the README for the wire protocol.
+
+extern crate omelette;
+extern crate rand;
+
+use omelette::{
+  config::{parse_arg, Config},
+  net,
+  race::{self, Notice},
+  seed,
+  word_list::WordList,
+  ErrorKind, Result,
+};
+use std::{
+  collections::VecDeque,
+  env,
+  io::{self, prelude::*},
+  net::TcpListener,
+  path::PathBuf,
+};
+
+static USAGE: &str = "usage: omelette-server [options]
+
+options:
+  -c, --config <file>    read settings from <file> instead of the default
+  -d, --db <file>        use the word database stored in <file>
+  -l, --len <n|min-max>  play words of length n, or a random length in a range
+  -D, --difficulty <lvl> pick words by how common they are (easy, normal, hard)
+  -s, --seed <n>         generate puzzles from the given seed
+  -b, --bind <addr>      listen on <addr> instead of 0.0.0.0
+  -h, --help             print this message and exit";
+
+fn run() -> Result<()> {
+  let mut args: VecDeque<_> = env::args().collect();
+  args.pop_front(); // drop argv[0]
+
+  let mut config_path: Option<PathBuf> = None;
+  let mut seed: Option<u64> = None;
+  let mut bind = "0.0.0.0".to_string();
+  let mut arg_config = Config::default();
+
+  while let Some(arg) = args.pop_front() {
+    match &*arg {
+      "-h" | "--help" => {
+        println!("{}", USAGE);
+        return Ok(());
+      }
+      "-c" | "--config" => {
+        config_path = Some(parse_arg::<String>(&mut args, "a filename")?.into())
+      }
+      "-d" | "--db" => {
+        arg_config.db_path =
+          Some(parse_arg::<String>(&mut args, "a filename")?.into())
+      }
+      "-l" | "--len" => {
+        arg_config.word_len = Some(parse_arg(&mut args, "a word length")?)
+      }
+      "-D" | "--difficulty" => {
+        arg_config.difficulty = Some(parse_arg(&mut args, "a difficulty")?)
+      }
+      "-s" | "--seed" => seed = Some(parse_arg(&mut args, "a seed")?),
+      "-b" | "--bind" => bind = parse_arg(&mut args, "an address")?,
+      a => return Err(ErrorKind::UnknownArg(a.into()).into()),
+    }
+  }
+
+  let config = Config::load(config_path.as_ref().map(|p| p.as_path()))?
+    .merge(arg_config);
+
+  let difficulty = config.difficulty();
+  let rng = seed::rng_from_seed(seed.unwrap_or_else(rand::random));
+
+  let db_path = config.db_path();
+
+  if !db_path.exists() {
+    return Err(ErrorKind::NoDatabase(db_path.display().to_string()).into());
+  }
+
+  let words = WordList::new(&db_path.to_string_lossy());
+
+  let range = match config.word_len {
+    Some(r) => r,
+    None => return Err(ErrorKind::NoLength.into()),
+  };
+
+  let lens: Vec<_> = (range.min..range.max + 1)
+    .filter(|l| !words.get_set_keys(l, difficulty).is_empty())
+    .collect();
+
+  if lens.is_empty() {
+    return Err(ErrorKind::NoWords(range.min, range.max).into());
+  }
+
+  let listener = TcpListener::bind(&*net::with_port(&bind))?;
+
+  writeln!(io::stderr(), "listening on {}", listener.local_addr()?).unwrap();
+
+  race::serve(listener, &words, rng, lens, difficulty, |notice| {
+    match notice {
+      Notice::Round {
+        round,
+        letters,
+        words,
+      } => writeln!(
+        io::stderr(),
+        "round {}: {} letters, {} words",
+        round,
+        letters,
+        words
+      ),
+      Notice::Joined(name) => writeln!(io::stderr(), "{} joined", name),
+      Notice::Left(name) => writeln!(io::stderr(), "{} left", name),
+    }.unwrap()
+  });
+
+  Ok(())
+}
+
+fn main() {
+  match run() {
+    Ok(_) => return,
+    Err(e) => writeln!(io::stderr(), "an error occurred: {}", e).unwrap(),
+  }
+}
@@ -0,0 +1,388 @@
+use diesel::{
+  self, connection::SimpleConnection, prelude::*, sqlite::SqliteConnection,
+};
+use mode::GameMode;
+use score::RoundScore;
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::Path,
+  time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use Result;
+
+static MAX_MISSED: usize = 10;
+static SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+// The stats database lives apart from the word database, since that one gets
+// regenerated.  Tables are created on first use.
+static SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS rounds (
+  id INTEGER PRIMARY KEY NOT NULL,
+  player TEXT NOT NULL,
+  played_at BIGINT NOT NULL,
+  key TEXT NOT NULL,
+  len INTEGER NOT NULL,
+  mode TEXT NOT NULL,
+  found INTEGER NOT NULL,
+  total INTEGER NOT NULL,
+  bonus INTEGER NOT NULL,
+  points INTEGER NOT NULL,
+  secs INTEGER NOT NULL,
+  forfeit BOOLEAN NOT NULL,
+  hints INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS round_words (
+  oid INTEGER PRIMARY KEY NOT NULL,
+  round INTEGER NOT NULL REFERENCES rounds (id),
+  norm TEXT NOT NULL,
+  found BOOLEAN NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS round_words_round ON round_words (round);
+";
+
+mod schema {
+  table! {
+    rounds (id) {
+      id -> Integer,
+      player -> Text,
+      played_at -> BigInt,
+      key -> Text,
+      len -> Integer,
+      mode -> Text,
+      found -> Integer,
+      total -> Integer,
+      bonus -> Integer,
+      points -> Integer,
+      secs -> Integer,
+      forfeit -> Bool,
+      hints -> Integer,
+    }
+  }
+
+  table! {
+    round_words (oid) {
+      oid -> Integer,
+      round -> Integer,
+      norm -> Text,
+      found -> Bool,
+    }
+  }
+
+  joinable!(round_words -> rounds (round));
+
+  allow_tables_to_appear_in_same_query!(rounds, round_words);
+}
+
+use self::schema::{round_words, rounds};
+
+#[derive(Insertable)]
+#[table_name = "rounds"]
+struct NewRound<'a> {
+  player: &'a str,
+  played_at: i64,
+  key: &'a str,
+  len: i32,
+  mode: &'a str,
+  found: i32,
+  total: i32,
+  bonus: i32,
+  points: i32,
+  secs: i32,
+  forfeit: bool,
+  hints: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "round_words"]
+struct NewRoundWord<'a> {
+  round: i32,
+  norm: &'a str,
+  found: bool,
+}
+
+#[derive(Queryable)]
+struct RoundQ {
+  played_at: i64,
+  len: i32,
+  found: i32,
+  total: i32,
+  points: i32,
+  secs: i32,
+  forfeit: bool,
+}
+
+// Everything worth remembering about a finished round
+pub struct RoundRecord<'a> {
+  pub player: &'a str,
+  pub key: &'a str,
+  pub mode: GameMode,
+  pub score: &'a RoundScore,
+  pub time: Duration,
+  pub forfeit: bool,
+  pub words: Vec<(&'a str, bool)>, // Each word in the set, and if it was found
+}
+
+pub struct LenStats {
+  pub len: usize,
+  pub rounds: usize,
+  pub cleared: usize,
+  pub found: f64, // Fraction of words found
+  pub secs: f64,
+  pub points: f64,
+}
+
+pub struct Summary {
+  pub player: String,
+  pub rounds: usize,
+  pub cleared: usize,
+  pub forfeits: usize,
+  pub streak: usize, // Rounds cleared in a row
+  pub best_streak: usize,
+  pub days: usize, // Days played in a row
+  pub best_days: usize,
+  pub by_len: Vec<LenStats>,
+  pub missed: Vec<(String, usize)>,
+}
+
+impl Summary {
+  pub fn lines(&self) -> Vec<String> {
+    let mut ret =
+      vec![format!("statistics for {}", self.player), String::new()];
+
+    if self.rounds == 0 {
+      ret.push("no rounds played yet".into());
+      return ret;
+    }
+
+    ret.push(format!(
+      "rounds: {}  cleared: {}  forfeited: {}",
+      self.rounds, self.cleared, self.forfeits
+    ));
+    ret.push(format!(
+      "clear streak: {} (best {})",
+      self.streak, self.best_streak
+    ));
+    ret.push(format!(
+      "daily streak: {} (best {})",
+      self.days, self.best_days
+    ));
+    ret.push(String::new());
+
+    ret.push("length  rounds  cleared  found   time  points".into());
+
+    for l in &self.by_len {
+      let secs = l.secs.round() as u64;
+
+      ret.push(format!(
+        "{:>6}  {:>6}  {:>7}  {:>4.0}%  {:>2}:{:02}  {:>6.0}",
+        l.len,
+        l.rounds,
+        l.cleared,
+        l.found * 100.0,
+        secs / 60,
+        secs % 60,
+        l.points
+      ));
+    }
+
+    if !self.missed.is_empty() {
+      ret.push(String::new());
+      ret.push("most missed:".into());
+
+      for &(ref norm, count) in &self.missed {
+        ret.push(format!("  {} ({})", norm, count));
+      }
+    }
+
+    ret
+  }
+}
+
+pub struct Stats {
+  conn: SqliteConnection,
+}
+
+impl Stats {
+  pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+    let conn = SqliteConnection::establish(&path.as_ref().to_string_lossy())?;
+
+    conn.batch_execute(SCHEMA)?;
+
+    Ok(Self { conn })
+  }
+
+  pub fn record(&self, round: &RoundRecord) -> Result<()> {
+    let played_at = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs() as i64)
+      .unwrap_or(0);
+
+    let score = round.score;
+
+    let new_round = NewRound {
+      player: round.player,
+      played_at,
+      key: round.key,
+      len: round.key.chars().count() as i32,
+      mode: round.mode.name(),
+      found: score.found() as i32,
+      total: score.total() as i32,
+      bonus: score.bonus() as i32,
+      points: score.points() as i32,
+      secs: round.time.as_secs() as i32,
+      forfeit: round.forfeit,
+      hints: score.hints() as i32,
+    };
+
+    self.conn.transaction::<_, diesel::result::Error, _>(|| {
+      diesel::insert_into(rounds::table)
+        .values(&new_round)
+        .execute(&self.conn)?;
+
+      let id: i32 = rounds::table
+        .select(rounds::id)
+        .order(rounds::id.desc())
+        .first(&self.conn)?;
+
+      let words: Vec<_> = round
+        .words
+        .iter()
+        .map(|&(norm, found)| NewRoundWord {
+          round: id,
+          norm,
+          found,
+        })
+        .collect();
+
+      diesel::insert_into(round_words::table)
+        .values(&words)
+        .execute(&self.conn)?;
+
+      Ok(())
+    })?;
+
+    Ok(())
+  }
+
+  pub fn summary(&self, player: &str) -> Result<Summary> {
+    let results = rounds::table
+      .filter(rounds::player.eq(player))
+      .order(rounds::id.asc())
+      .select((
+        rounds::played_at,
+        rounds::len,
+        rounds::found,
+        rounds::total,
+        rounds::points,
+        rounds::secs,
+        rounds::forfeit,
+      ))
+      .load::<RoundQ>(&self.conn)?;
+
+    let missed = round_words::table
+      .inner_join(rounds::table)
+      .filter(rounds::player.eq(player))
+      .filter(round_words::found.eq(false))
+      .select(round_words::norm)
+      .load::<String>(&self.conn)?;
+
+    let mut ret = Summary {
+      player: player.into(),
+      rounds: results.len(),
+      cleared: 0,
+      forfeits: 0,
+      streak: 0,
+      best_streak: 0,
+      days: 0,
+      best_days: 0,
+      by_len: Vec::new(),
+      missed: Vec::new(),
+    };
+
+    let mut by_len: BTreeMap<usize, Vec<&RoundQ>> = BTreeMap::new();
+    let mut days: Vec<i64> = Vec::new();
+
+    for r in &results {
+      if r.found >= r.total {
+        ret.cleared = ret.cleared + 1;
+        ret.streak = ret.streak + 1;
+        ret.best_streak = ret.best_streak.max(ret.streak);
+      } else {
+        ret.streak = 0;
+      }
+
+      if r.forfeit {
+        ret.forfeits = ret.forfeits + 1;
+      }
+
+      by_len
+        .entry(r.len as usize)
+        .or_insert_with(Vec::new)
+        .push(r);
+      days.push(r.played_at / SECS_PER_DAY);
+    }
+
+    days.dedup(); // Rounds are in order, so this leaves each day once
+
+    let mut run = 0;
+    let mut last: Option<i64> = None;
+
+    for day in &days {
+      run = match last {
+        Some(l) if l + 1 == *day => run + 1,
+        _ => 1,
+      };
+
+      ret.best_days = ret.best_days.max(run);
+      last = Some(*day);
+    }
+
+    // The daily streak only counts if it's still alive
+    let today = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|d| d.as_secs() as i64 / SECS_PER_DAY)
+      .unwrap_or(0);
+
+    ret.days = match last {
+      Some(l) if l + 1 >= today => run,
+      _ => 0,
+    };
+
+    ret.by_len = by_len
+      .into_iter()
+      .map(|(len, rs)| {
+        let n = rs.len() as f64;
+
+        LenStats {
+          len,
+          rounds: rs.len(),
+          cleared: rs.iter().filter(|r| r.found >= r.total).count(),
+          found: rs
+            .iter()
+            .map(|r| r.found as f64 / (r.total as f64).max(1.0))
+            .sum::<f64>()
+            / n,
+          secs: rs.iter().map(|r| r.secs as f64).sum::<f64>() / n,
+          points: rs.iter().map(|r| r.points as f64).sum::<f64>() / n,
+        }
+      })
+      .collect();
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for norm in missed {
+      *counts.entry(norm).or_insert(0) += 1;
+    }
+
+    let mut missed: Vec<_> = counts.into_iter().collect();
+
+    missed.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    missed.truncate(MAX_MISSED);
+
+    ret.missed = missed;
+
+    Ok(ret)
+  }
+}
@@ -0,0 +1,99 @@
+use tui::prelude_internal::*;
+
+// A single line listing words found outside the current set, newest first
+pub struct BonusBox {
+  coredata: ElementCoreData,
+  rect: Rect,
+  width: i32,
+  words: Vec<String>,
+  pairs: Vec<Option<i32>>, // In hot-seat play, the color of each word's finder
+  hl: Option<usize>,
+  hl_pair: i32,
+}
+
+impl BonusBox {
+  pub fn new(hl_pair: i32) -> Self {
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      width: 0,
+      words: Vec::new(),
+      pairs: Vec::new(),
+      hl: None,
+      hl_pair,
+    }
+  }
+
+  pub fn push(&mut self, word: &str, pair: Option<i32>) {
+    self.words.push(word.to_string());
+    self.pairs.push(pair);
+    self.hl = None;
+  }
+
+  pub fn set_highlight(&mut self, word: Option<&str>) {
+    self.hl = word.and_then(|w| self.words.iter().position(|v| v == w));
+  }
+
+  fn title_str(&self) -> String {
+    format!("bonus ({}):", self.words.len())
+  }
+}
+
+impl ElementCore for BonusBox {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    MeasureSize {
+      w: Some(self.title_str().len() as i32),
+      h: Some(1),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.width = space.size.w;
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size {
+        w: space.size.w,
+        h: 1,
+      },
+    };
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+
+    if !self.words.is_empty() {
+      let title = self.title_str();
+      let mut x = title.len() as i32;
+
+      surface.put_str(Point { x: 0, y: 0 }, &title);
+
+      for (i, word) in self.words.iter().enumerate().rev() {
+        if x + 1 + str_width(word) as i32 > self.width {
+          break;
+        }
+
+        let pair = if self.hl == Some(i) {
+          Some(self.hl_pair)
+        } else {
+          self.pairs[i]
+        };
+
+        surface.set_pair(pair);
+        surface.put_str(Point { x: x + 1, y: 0 }, word);
+        surface.set_pair(None);
+
+        x = x + 1 + str_width(word) as i32;
+      }
+    }
+  }
+}
@@ -0,0 +1,109 @@
+use keymap::Action;
+use tui::prelude_internal::*;
+
+// A row of buttons for mouse users, drawn as [label]s a space apart.  Clicking
+// one has the same effect as a key bound to its action.
+pub struct ButtonBar {
+  coredata: ElementCoreData,
+  rect: Rect,
+  pos: Point,
+  labels: Vec<String>,
+  actions: Vec<Action>,
+}
+
+impl ButtonBar {
+  pub fn new(buttons: Vec<(String, Action)>) -> Self {
+    let (labels, actions) = buttons.into_iter().unzip();
+
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      pos: Point { x: 0, y: 0 },
+      labels,
+      actions,
+    }
+  }
+
+  // The index of the button at the given screen position, if any
+  fn button_at(&self, at: Point) -> Option<usize> {
+    if at.y != self.pos.y {
+      return None;
+    }
+
+    let mut x = self.pos.x;
+
+    for (i, label) in self.labels.iter().enumerate() {
+      let w = label.chars().count() as i32 + 2;
+
+      if at.x >= x && at.x < x + w {
+        return Some(i);
+      }
+
+      x = x + w + 1;
+    }
+
+    None
+  }
+
+  fn width(&self) -> i32 {
+    self
+      .labels
+      .iter()
+      .map(|l| l.chars().count() as i32 + 3)
+      .sum::<i32>()
+      - 1
+  }
+}
+
+impl ElementCore for ButtonBar {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    MeasureSize {
+      w: Some(self.width().max(0)),
+      h: Some(1),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.pos = space.pos;
+
+    let w = self.width().max(1);
+
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size { w, h: 1 },
+    };
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+    surface.move_cur(Point { x: 0, y: 0 });
+
+    for (i, label) in self.labels.iter().enumerate() {
+      if i > 0 {
+        surface.add_str(" ");
+      }
+
+      surface.add_str(&format!("[{}]", label));
+    }
+  }
+
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    match event {
+      Event::Click(at) => match self.button_at(at) {
+        Some(i) => Some(Event::Action(self.actions[i])),
+        None => Some(event),
+      },
+      _ => Some(event),
+    }
+  }
+}
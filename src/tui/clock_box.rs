@@ -0,0 +1,78 @@
+use std::time::Duration;
+use tui::prelude_internal::*;
+
+static LOW_SECS: u64 = 10;
+
+pub struct ClockBox {
+  coredata: ElementCoreData,
+  rect: Rect,
+  secs: u64,
+  low_pair: i32,
+}
+
+impl ClockBox {
+  pub fn new(limit: Duration, low_pair: i32) -> Self {
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      secs: Self::ceil_secs(limit),
+      low_pair,
+    }
+  }
+
+  fn ceil_secs(d: Duration) -> u64 {
+    d.as_secs() + if d.subsec_nanos() > 0 { 1 } else { 0 }
+  }
+
+  pub fn set_remaining(&mut self, val: Duration) {
+    self.secs = Self::ceil_secs(val);
+  }
+
+  fn displayed_str(&self) -> String {
+    format!("{}:{:02}", self.secs / 60, self.secs % 60)
+  }
+}
+
+impl ElementCore for ClockBox {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    MeasureSize {
+      w: Some(self.displayed_str().len() as i32),
+      h: Some(1),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size {
+        w: space.size.w,
+        h: 1,
+      },
+    };
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+
+    let pair = if self.secs <= LOW_SECS {
+      Some(self.low_pair)
+    } else {
+      None
+    };
+    let text = self.displayed_str();
+
+    surface.set_pair(pair);
+    surface.put_str(Point { x: 0, y: 0 }, &text);
+    surface.set_pair(None);
+  }
+}
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy)]
+use std::cmp;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct Point {
   pub x: i32,
   pub y: i32,
@@ -27,3 +29,37 @@ impl Default for MeasureSize {
     Self { w: None, h: None }
   }
 }
+
+impl Default for Rect {
+  fn default() -> Self {
+    Self {
+      pos: Point { x: 0, y: 0 },
+      size: Size { w: 0, h: 0 },
+    }
+  }
+}
+
+impl Rect {
+  pub fn contains(&self, at: Point) -> bool {
+    at.x >= self.pos.x
+      && at.y >= self.pos.y
+      && at.x < self.pos.x + self.size.w
+      && at.y < self.pos.y + self.size.h
+  }
+
+  // The part of this rect that's also in the other one (which may be empty)
+  pub fn intersect(&self, other: &Rect) -> Rect {
+    let x = cmp::max(self.pos.x, other.pos.x);
+    let y = cmp::max(self.pos.y, other.pos.y);
+    let right = cmp::min(self.pos.x + self.size.w, other.pos.x + other.size.w);
+    let bottom = cmp::min(self.pos.y + self.size.h, other.pos.y + other.size.h);
+
+    Rect {
+      pos: Point { x, y },
+      size: Size {
+        w: cmp::max(0, right - x),
+        h: cmp::max(0, bottom - y),
+      },
+    }
+  }
+}
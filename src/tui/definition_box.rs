@@ -0,0 +1,138 @@
+use tui::prelude_internal::*;
+
+// A side panel showing the definition of the selected word.  It always asks
+// for the same width so that the layout doesn't jump around as words are
+// selected.
+pub struct DefinitionBox {
+  coredata: ElementCoreData,
+  rect: Rect,
+  width: i32,
+  title_pair: i32,
+  word: Option<String>,
+  definition: Option<String>,
+}
+
+impl DefinitionBox {
+  pub fn new(width: i32, title_pair: i32) -> Self {
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      width,
+      title_pair,
+      word: None,
+      definition: None,
+    }
+  }
+
+  pub fn set_word(&mut self, word: &str, definition: Option<String>) {
+    self.word = Some(word.into());
+    self.definition = definition;
+  }
+}
+
+// Breaks text into lines of at most width characters, splitting words only
+// if they don't fit on a line of their own
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+  let mut ret = Vec::new();
+  let mut line = String::new();
+
+  if width == 0 {
+    return ret;
+  }
+
+  for word in text.split_whitespace() {
+    let mut word: Vec<char> = word.chars().collect();
+    let line_len = line.chars().count();
+
+    if line_len > 0 && line_len + 1 + word.len() > width {
+      ret.push(line);
+      line = String::new();
+    }
+
+    while word.len() > width {
+      let rest = word.split_off(width);
+
+      ret.push(word.into_iter().collect());
+      word = rest;
+    }
+
+    if !line.is_empty() {
+      line.push(' ');
+    }
+
+    line.extend(word);
+  }
+
+  if !line.is_empty() {
+    ret.push(line);
+  }
+
+  ret
+}
+
+impl ElementCore for DefinitionBox {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    MeasureSize {
+      w: Some(self.width),
+      h: Some(0),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.rect = space;
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+
+    if self.rect.size.w < 3 || self.rect.size.h < 1 {
+      return;
+    }
+
+    // Leave a column for the border and one for padding
+    let text_w = (self.rect.size.w - 2) as usize;
+
+    surface.vline(Point { x: 0, y: 0 }, self.rect.size.h);
+
+    let mut lines = match self.word {
+      Some(ref w) => {
+        surface.set_pair(Some(self.title_pair));
+        surface.put_str(
+          Point { x: 2, y: 0 },
+          &w.chars().take(text_w).collect::<String>(),
+        );
+        surface.set_pair(None);
+
+        match self.definition {
+          Some(ref d) => wrap_text(d, text_w),
+          None => vec!["no definition found".into()],
+        }
+      }
+      None => wrap_text("select a found word with [ and ]", text_w),
+    };
+
+    let top = if self.word.is_some() { 2 } else { 0 };
+
+    lines.truncate((self.rect.size.h - top).max(0) as usize);
+
+    for (i, line) in lines.iter().enumerate() {
+      surface.put_str(
+        Point {
+          x: 2,
+          y: top + i as i32,
+        },
+        line,
+      );
+    }
+  }
+}
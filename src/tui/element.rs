@@ -20,9 +20,21 @@ pub trait Element {
 
   fn arrange(&mut self, Rect);
 
-  fn render(&mut self);
+  // Draws the element onto the surface, within the rect it was arranged in
+  fn render(&mut self, &mut dyn Surface);
 
-  fn render_cur(&mut self);
+  // Puts the cursor where the element with focus wants it
+  fn render_cur(&mut self, &mut dyn Surface);
+
+  // Gives an event to this element, returning it if it wasn't handled, or the
+  // event to handle in its place (such as the action for a button)
+  fn handle(&mut self, Event) -> Option<Event>;
+
+  fn has_focus(&self) -> bool;
+
+  // Moves focus to the next element that can take it (or the previous one),
+  // returning false once focus has moved out past the last of them
+  fn move_focus(&mut self, bool) -> bool;
 }
 
 impl<T> Element for T
@@ -43,13 +55,26 @@ where
     self.arrange_impl(space);
   }
 
-  fn render(&mut self) {
-    self.render_impl();
+  fn render(&mut self, surface: &mut dyn Surface) {
+    self.render_impl(surface);
+  }
+
+  #[inline]
+  fn render_cur(&mut self, surface: &mut dyn Surface) {
+    self.render_cur_impl(surface);
+  }
+
+  fn handle(&mut self, event: Event) -> Option<Event> {
+    self.handle_impl(event)
   }
 
   #[inline]
-  fn render_cur(&mut self) {
-    self.render_cur_impl();
+  fn has_focus(&self) -> bool {
+    self.has_focus_impl()
+  }
+
+  fn move_focus(&mut self, forward: bool) -> bool {
+    self.move_focus_impl(forward)
   }
 }
 
@@ -70,3 +95,64 @@ where
 {
   Rc::clone(el) as ElemRef
 }
+
+// Clicks and scrolling are given to each child in turn, and anything else to
+// the child with focus first.  Events a child passes on unchanged go on to the
+// next one.
+pub fn handle_children(children: &[ElemRef], event: Event) -> Option<Event> {
+  let mut order: Vec<_> = children.iter().collect();
+
+  match event {
+    Event::Click(_) | Event::Scroll(..) => {}
+    _ => {
+      if let Some(i) = order.iter().position(|c| c.borrow().has_focus()) {
+        let child = order.remove(i);
+        order.insert(0, child);
+      }
+    }
+  }
+
+  for child in order {
+    match child.borrow_mut().handle(event) {
+      Some(e) if e == event => {}
+      ret => return ret,
+    }
+  }
+
+  Some(event)
+}
+
+pub fn children_have_focus(children: &[ElemRef]) -> bool {
+  children.iter().any(|c| c.borrow().has_focus())
+}
+
+// Focus moves on inside the child that has it first, then into the children
+// after it
+pub fn move_children_focus(children: &[ElemRef], forward: bool) -> bool {
+  let mut order: Vec<_> = children.iter().collect();
+
+  if !forward {
+    order.reverse();
+  }
+
+  let start = order
+    .iter()
+    .position(|c| c.borrow().has_focus())
+    .unwrap_or(0);
+
+  order[start..]
+    .iter()
+    .any(|c| c.borrow_mut().move_focus(forward))
+}
+
+pub fn render_children(children: &[ElemRef], surface: &mut dyn Surface) {
+  for child in children {
+    child.borrow_mut().render(surface);
+  }
+}
+
+pub fn render_children_cur(children: &[ElemRef], surface: &mut dyn Surface) {
+  if let Some(child) = children.iter().find(|c| c.borrow().has_focus()) {
+    child.borrow_mut().render_cur(surface);
+  }
+}
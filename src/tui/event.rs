@@ -0,0 +1,37 @@
+use keymap::{read_input, Action, Input, KeyCodes};
+use mouse::{self, Press};
+use nc;
+use tui::core::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+  Action(Action), // A key bound to an action, or a button standing in for one
+  Key(Input),     // Any other key
+  Click(Point),
+  Scroll(Point, i32), // A turn of the mouse wheel: -1 for up, 1 for down
+  Resize,
+  Timeout, // Nothing happened before the window's timeout ran out
+}
+
+impl Event {
+  // Waits for the next input and turns it into an event, giving keys bound to
+  // actions as those actions.  Returns None for input that means nothing, such
+  // as mouse events other than clicks and scrolling.
+  pub fn read(win: nc::WINDOW, keys: &KeyCodes) -> Option<Self> {
+    let input = read_input(win);
+
+    Some(match input {
+      Input::Code(nc::KEY_MOUSE) => match mouse::read_press()? {
+        (at, Press::Left) => Event::Click(at),
+        (at, Press::WheelUp) => Event::Scroll(at, -1),
+        (at, Press::WheelDown) => Event::Scroll(at, 1),
+      },
+      Input::Code(nc::KEY_RESIZE) => Event::Resize,
+      Input::Timeout => Event::Timeout,
+      _ => match keys.action(input) {
+        Some(action) => Event::Action(action),
+        None => Event::Key(input),
+      },
+    })
+  }
+}
@@ -1,7 +1,4 @@
-use std::{
-  cmp,
-  collections::{HashMap, HashSet},
-};
+use std::cmp;
 use tui::prelude_internal::*;
 
 pub enum GridLength {
@@ -10,63 +7,289 @@ pub enum GridLength {
   Content,
 }
 
+// A row or column, kept between a minimum and (optionally) a maximum length
+pub struct GridTrack {
+  pub len: GridLength,
+  pub min: i32,
+  pub max: Option<i32>,
+}
+
+impl GridLength {
+  pub fn bounded(self, min: i32, max: Option<i32>) -> GridTrack {
+    GridTrack {
+      len: self,
+      min,
+      max,
+    }
+  }
+}
+
+impl From<GridLength> for GridTrack {
+  fn from(len: GridLength) -> Self {
+    len.bounded(0, None)
+  }
+}
+
+impl GridTrack {
+  fn clamp(&self, len: i32) -> i32 {
+    let len = cmp::max(len, self.min);
+
+    self.max.map_or(len, |m| cmp::min(len, m))
+  }
+}
+
+pub enum GridAlign {
+  Begin,
+  Middle,
+  End,
+  Stretch,
+}
+
+impl GridAlign {
+  // The position and length of a child within the given stretch of a row or
+  // column
+  fn place(&self, pos: i32, len: i32, desired: Option<i32>) -> (i32, i32) {
+    use self::GridAlign::*;
+
+    let size = desired.map_or(len, |d| cmp::min(d, len));
+
+    match self {
+      Begin => (pos, size),
+      Middle => (pos + (len - size) / 2, size),
+      End => (pos + len - size, size),
+      Stretch => (pos, len),
+    }
+  }
+}
+
+// Where a child goes: the cell at its top-left corner, how many rows and
+// columns it spans, and how it sits in that space if it wants less of it
+pub struct GridCell {
+  pub row: usize,
+  pub col: usize,
+  pub row_span: usize,
+  pub col_span: usize,
+  pub h_align: GridAlign,
+  pub v_align: GridAlign,
+}
+
+impl Default for GridCell {
+  fn default() -> Self {
+    Self {
+      row: 0,
+      col: 0,
+      row_span: 1,
+      col_span: 1,
+      h_align: GridAlign::Stretch,
+      v_align: GridAlign::Stretch,
+    }
+  }
+}
+
+impl From<(usize, usize)> for GridCell {
+  fn from((row, col): (usize, usize)) -> Self {
+    Self {
+      row,
+      col,
+      ..Default::default()
+    }
+  }
+}
+
 pub struct Grid<'a> {
   coredata: ElementCoreData,
-  control_cells: HashMap<(usize, usize), HashSet<usize>>,
+  cells: Vec<GridCell>,
   row_sizes: Vec<i32>,
   col_sizes: Vec<i32>,
   children: Vec<ElemRef<'a>>,
-  rows: Vec<GridLength>,
-  cols: Vec<GridLength>,
+  rows: Vec<GridTrack>,
+  cols: Vec<GridTrack>,
 }
 
-impl<'a> Grid<'a> {
-  pub fn new<IC, IR, IK>(children: IC, rows: IR, cols: IK) -> Self
-  where
-    IC: IntoIterator<Item = (ElemRef<'a>, (usize, usize))>,
-    IR: IntoIterator<Item = GridLength>,
-    IK: IntoIterator<Item = GridLength>,
-  {
-    let mut control_cells: HashMap<(usize, usize), HashSet<usize>> =
-      HashMap::new();
+// The rows or columns a child covers, leaving out any past the edge of the grid
+fn span(start: usize, len: usize, count: usize) -> (usize, usize) {
+  (cmp::min(start, count), cmp::min(start + len, count))
+}
 
-    let children = children
-      .into_iter()
-      .enumerate()
-      .map(|(i, (child, cell))| {
-        use std::collections::hash_map::Entry::*;
-
-        match control_cells.entry(cell) {
-          Vacant(v) => {
-            v.insert(HashSet::new()).insert(i);
-          }
-          Occupied(o) => {
-            o.into_mut().insert(i);
-          }
-        }
+fn has_content(tracks: &[GridTrack], (start, end): (usize, usize)) -> bool {
+  tracks[start..end].iter().any(|t| match t.len {
+    GridLength::Content => true,
+    _ => false,
+  })
+}
+
+fn span_len(sizes: &[i32], (start, end): (usize, usize)) -> i32 {
+  sizes[start..end].iter().sum()
+}
+
+// Splits extra space by weight, handing out what's left after rounding down to
+// whichever shares were rounded down the most
+fn share(extra: i32, weights: &[f32]) -> Vec<i32> {
+  let total: f32 = weights.iter().sum();
+
+  if extra <= 0 || total <= 0.0 {
+    return vec![0; weights.len()];
+  }
 
-        child
+  let exact: Vec<f32> =
+    weights.iter().map(|w| extra as f32 * w / total).collect();
+  let mut ret: Vec<i32> = exact.iter().map(|e| e.floor() as i32).collect();
+  let left = extra - ret.iter().sum::<i32>();
+
+  let mut order: Vec<usize> = (0..ret.len()).collect();
+  order.sort_by(|&a, &b| {
+    (exact[b] - exact[b].floor())
+      .partial_cmp(&(exact[a] - exact[a].floor()))
+      .unwrap()
+  });
+
+  for &i in order.iter().take(cmp::max(0, left) as usize) {
+    ret[i] = ret[i] + 1;
+  }
+
+  ret
+}
+
+// Sizes a set of rows or columns, given the space they have to fill and the
+// (start, end, length) of each child that needs room in the content ones
+fn track_sizes(
+  tracks: &[GridTrack],
+  space: Option<i32>,
+  needs: &[(usize, usize, i32)],
+) -> Vec<i32> {
+  use self::GridLength::*;
+
+  let mut sizes: Vec<i32> = tracks
+    .iter()
+    .map(|t| match t.len {
+      Static(n) => t.clamp(n),
+      _ => t.clamp(0),
+    })
+    .collect();
+
+  // Children spanning one track go first, so that a child spanning several
+  // only adds whatever room they haven't already made
+  let mut needs = needs.to_vec();
+  needs.sort_by_key(|&(start, end, _)| end - start);
+
+  for (start, end, len) in needs {
+    let content: Vec<usize> = (start..end)
+      .filter(|&i| match tracks[i].len {
+        Content => true,
+        _ => false,
+      })
+      .collect();
+
+    let short = len - span_len(&sizes, (start, end));
+    let extra = share(short, &vec![1.0; content.len()]);
+
+    for (&i, extra) in content.iter().zip(extra) {
+      sizes[i] = sizes[i] + extra;
+    }
+  }
+
+  for (size, track) in sizes.iter_mut().zip(tracks) {
+    if let Content = track.len {
+      *size = track.clamp(*size);
+    }
+  }
+
+  // Dynamic tracks share what's left by weight.  Any that would go past their
+  // bounds are held there, and the rest share what's left after that.
+  let mut dynamic: Vec<usize> = (0..tracks.len())
+    .filter(|&i| match tracks[i].len {
+      Dynamic(_) => true,
+      _ => false,
+    })
+    .collect();
+
+  let used: i32 = (0..tracks.len())
+    .filter(|i| !dynamic.contains(i))
+    .map(|i| sizes[i])
+    .sum();
+
+  let mut free = space.map_or(0, |s| cmp::max(0, s - used));
+
+  loop {
+    let weights: Vec<f32> = dynamic
+      .iter()
+      .map(|&i| match tracks[i].len {
+        Dynamic(w) => w,
+        _ => unreachable!(),
       })
       .collect();
 
-    let rows: Vec<_> = rows.into_iter().collect();
-    let cols: Vec<_> = cols.into_iter().collect();
+    let shares = share(free, &weights);
+
+    let held: Vec<(usize, i32)> = dynamic
+      .iter()
+      .zip(&shares)
+      .filter_map(|(&i, &s)| {
+        let len = tracks[i].clamp(s);
+
+        if len == s {
+          None
+        } else {
+          Some((i, len))
+        }
+      })
+      .collect();
 
-    let mut row_sizes = Vec::with_capacity(rows.len());
-    let mut col_sizes = Vec::with_capacity(cols.len());
+    if held.is_empty() {
+      for (&i, &s) in dynamic.iter().zip(&shares) {
+        sizes[i] = s;
+      }
 
-    for _ in 0..rows.len() {
-      row_sizes.push(0);
+      break;
     }
-    for _ in 0..cols.len() {
-      col_sizes.push(0);
+
+    for &(i, len) in &held {
+      sizes[i] = len;
+      free = cmp::max(0, free - len);
     }
 
+    dynamic.retain(|i| !held.iter().any(|&(j, _)| j == *i));
+  }
+
+  sizes
+}
+
+// The position of the start of each track, plus the end of the last one
+fn positions(sizes: &[i32]) -> Vec<i32> {
+  let mut pos = 0;
+  let mut ret = vec![0];
+
+  for size in sizes {
+    pos = pos + size;
+    ret.push(pos);
+  }
+
+  ret
+}
+
+impl<'a> Grid<'a> {
+  pub fn new<IC, C, IR, R, IK, K>(children: IC, rows: IR, cols: IK) -> Self
+  where
+    IC: IntoIterator<Item = (ElemRef<'a>, C)>,
+    C: Into<GridCell>,
+    IR: IntoIterator<Item = R>,
+    R: Into<GridTrack>,
+    IK: IntoIterator<Item = K>,
+    K: Into<GridTrack>,
+  {
+    let (children, cells): (Vec<_>, Vec<_>) = children
+      .into_iter()
+      .map(|(child, cell)| (child, cell.into()))
+      .unzip();
+
+    let rows: Vec<GridTrack> = rows.into_iter().map(|r| r.into()).collect();
+    let cols: Vec<GridTrack> = cols.into_iter().map(|c| c.into()).collect();
+
     Self {
       coredata: Default::default(),
-      control_cells,
-      row_sizes,
-      col_sizes,
+      cells,
+      row_sizes: vec![0; rows.len()],
+      col_sizes: vec![0; cols.len()],
       children,
       rows,
       cols,
@@ -84,253 +307,344 @@ impl<'a> ElementCore for Grid<'a> {
   }
 
   fn measure_impl(&mut self, space: MeasureSize) -> MeasureSize {
-    use std::collections::hash_map::Entry::*;
-    use GridLength::*;
+    // Columns go first, so that children in content rows can be told how wide
+    // they'll be
+    let mut needs = Vec::new();
 
-    let mut static_cells: Vec<(usize, usize)> = Vec::new();
-    let mut dynamic_cells: Vec<(usize, usize)> = Vec::new(); // TODO: is this necessary?
-    let mut content_cells: Vec<(usize, usize)> = Vec::new();
+    for (child, cell) in self.children.iter().zip(&self.cells) {
+      let cols = span(cell.col, cell.col_span, self.cols.len());
+      let rows = span(cell.row, cell.row_span, self.rows.len());
 
-    let mut row_sizes: HashMap<usize, i32> = HashMap::new();
-    let mut col_sizes: HashMap<usize, i32> = HashMap::new();
+      if !has_content(&self.cols, cols) {
+        continue;
+      }
 
-    for (i, row) in self.rows.iter().enumerate() {
-      for (j, col) in self.cols.iter().enumerate() {
-        let cell = (i, j);
+      let mut child = child.borrow_mut();
 
-        match row {
-          Static(_) => match col {
-            Static(_) => &mut static_cells,
-            Dynamic(_) => &mut dynamic_cells,
-            Content => &mut content_cells,
-          },
-          Dynamic(_) => match col {
-            Static(_) => &mut dynamic_cells,
-            Dynamic(_) => &mut dynamic_cells,
-            Content => &mut content_cells,
-          },
-          Content => match col {
-            Static(_) => &mut content_cells,
-            Dynamic(_) => &mut content_cells,
-            Content => &mut content_cells,
-          },
-        }.push(cell);
-      }
+      child.measure(MeasureSize {
+        w: Some(0),
+        h: if has_content(&self.rows, rows) {
+          Some(0)
+        } else {
+          None
+        },
+      });
+
+      needs.push((cols.0, cols.1, child.desired_size().w.unwrap_or(0)));
     }
 
-    for (i, j) in static_cells {
-      match self.cols[j] {
-        Static(w) => {
-          col_sizes.insert(j, w);
-        }
-        _ => unreachable!(),
-      }
+    self.col_sizes = track_sizes(&self.cols, space.w, &needs);
 
-      match self.rows[i] {
-        Static(h) => {
-          row_sizes.insert(i, h);
-        }
-        _ => unreachable!(),
-      }
-    }
+    let mut needs = Vec::new();
 
-    for (i, j) in content_cells {
-      let cell = (i, j);
-
-      match self.rows[i] {
-        Content => match self.control_cells.get(&cell) {
-          Some(c) => for k in c {
-            let mut child = self.children[*k].borrow_mut();
-
-            child.measure(MeasureSize {
-              w: match self.cols[j] {
-                Content => Some(0),
-                _ => None,
-              },
-              h: Some(0),
-            });
-
-            let size = child.desired_size().h.unwrap_or(0);
-
-            match row_sizes.entry(i) {
-              Vacant(v) => {
-                v.insert(size);
-              }
-              Occupied(o) => {
-                let v = o.into_mut();
-                *v = cmp::max(*v, size);
-              }
-            }
-          },
-          None => (),
-        },
-        _ => match self.cols[j] {
-          Content => match self.control_cells.get(&cell) {
-            Some(c) => for k in c {
-              let mut child = self.children[*k].borrow_mut();
-
-              child.measure(MeasureSize {
-                w: Some(0),
-                h: None,
-              });
-
-              let size = child.desired_size().w.unwrap_or(0);
-
-              match col_sizes.entry(j) {
-                Vacant(v) => {
-                  v.insert(size);
-                }
-                Occupied(o) => {
-                  let v = o.into_mut();
-                  *v = cmp::max(*v, size);
-                }
-              }
-            },
-            None => (),
-          },
-          _ => unreachable!(),
-        },
+    for (child, cell) in self.children.iter().zip(&self.cells) {
+      let cols = span(cell.col, cell.col_span, self.cols.len());
+      let rows = span(cell.row, cell.row_span, self.rows.len());
+
+      if !has_content(&self.rows, rows) {
+        continue;
       }
+
+      let mut child = child.borrow_mut();
+
+      child.measure(MeasureSize {
+        w: Some(span_len(&self.col_sizes, cols)),
+        h: Some(0),
+      });
+
+      needs.push((rows.0, rows.1, child.desired_size().h.unwrap_or(0)));
     }
 
-    let mut used_size = Size { w: 0, h: 0 };
-    let mut total_weight_r = 0.0;
-    let mut total_weight_c = 0.0;
+    self.row_sizes = track_sizes(&self.rows, space.h, &needs);
+
+    for (child, cell) in self.children.iter().zip(&self.cells) {
+      let cols = span(cell.col, cell.col_span, self.cols.len());
+      let rows = span(cell.row, cell.row_span, self.rows.len());
 
-    for (_, size) in &row_sizes {
-      used_size.h = used_size.h + size;
+      child.borrow_mut().measure(MeasureSize {
+        w: Some(span_len(&self.col_sizes, cols)),
+        h: Some(span_len(&self.row_sizes, rows)),
+      });
     }
 
-    for (_, size) in &col_sizes {
-      used_size.w = used_size.w + size;
+    MeasureSize {
+      w: Some(self.col_sizes.iter().sum()),
+      h: Some(self.row_sizes.iter().sum()),
     }
+  }
 
-    let free_space = Size {
-      w: space.w.map_or(0, |w| cmp::max(0, w - used_size.w)),
-      h: space.h.map_or(0, |h| cmp::max(0, h - used_size.h)),
-    };
+  // NB: ignoring the size because it would probably require re-measuring
+  fn arrange_impl(&mut self, space: Rect) {
+    let col_pos = positions(&self.col_sizes);
+    let row_pos = positions(&self.row_sizes);
 
-    for row in &self.rows {
-      match row {
-        Dynamic(w) => total_weight_r = total_weight_r + w,
-        _ => (),
-      }
+    for (child, cell) in self.children.iter().zip(&self.cells) {
+      let cols = span(cell.col, cell.col_span, self.cols.len());
+      let rows = span(cell.row, cell.row_span, self.rows.len());
+
+      let mut child = child.borrow_mut();
+      let desired = child.desired_size();
+
+      let (x, w) = cell.h_align.place(
+        col_pos[cols.0],
+        col_pos[cols.1] - col_pos[cols.0],
+        desired.w,
+      );
+      let (y, h) = cell.v_align.place(
+        row_pos[rows.0],
+        row_pos[rows.1] - row_pos[rows.0],
+        desired.h,
+      );
+
+      child.arrange(Rect {
+        pos: Point {
+          x: space.pos.x + x,
+          y: space.pos.y + y,
+        },
+        size: Size { w, h },
+      });
     }
+  }
 
-    for col in &self.cols {
-      match col {
-        Dynamic(w) => total_weight_c = total_weight_c + w,
-        _ => (),
-      }
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    render_children(&self.children, surface);
+  }
+
+  fn render_cur_impl(&mut self, surface: &mut dyn Surface) {
+    render_children_cur(&self.children, surface);
+  }
+
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    handle_children(&self.children, event)
+  }
+
+  fn has_focus_impl(&self) -> bool {
+    children_have_focus(&self.children)
+  }
+
+  fn move_focus_impl(&mut self, forward: bool) -> bool {
+    move_children_focus(&self.children, forward)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tui::element as el;
+
+  // Asks for a fixed size and remembers where it was put, as (x, y, w, h)
+  struct Probe {
+    coredata: ElementCoreData,
+    size: (i32, i32),
+    rect: Option<(i32, i32, i32, i32)>,
+  }
+
+  fn probe(w: i32, h: i32) -> el::ElemWrapper<Probe> {
+    el::wrap(Probe {
+      coredata: Default::default(),
+      size: (w, h),
+      rect: None,
+    })
+  }
+
+  impl ElementCore for Probe {
+    fn get_coredata(&self) -> &ElementCoreData {
+      &self.coredata
     }
 
-    // TODO: this does not handle rounding errors *at all*
-    for (i, row) in self.rows.iter().enumerate() {
-      match row {
-        Dynamic(h) => {
-          row_sizes.insert(
-            i,
-            (free_space.h as f32 * h / total_weight_r).round() as i32,
-          );
-        }
-        _ => (),
-      }
+    fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+      &mut self.coredata
     }
 
-    for (i, col) in self.cols.iter().enumerate() {
-      match col {
-        Dynamic(w) => {
-          col_sizes.insert(
-            i,
-            (free_space.w as f32 * w / total_weight_c).round() as i32,
-          );
-        }
-        _ => (),
+    fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+      MeasureSize {
+        w: Some(self.size.0),
+        h: Some(self.size.1),
       }
     }
 
-    for i in 0..self.rows.len() {
-      self.row_sizes[i] = row_sizes[&i];
+    fn arrange_impl(&mut self, space: Rect) {
+      self.rect = Some((space.pos.x, space.pos.y, space.size.w, space.size.h));
     }
 
-    for i in 0..self.cols.len() {
-      self.col_sizes[i] = col_sizes[&i];
-    }
+    fn render_impl(&mut self, _: &mut dyn Surface) {}
+  }
 
-    for i in 0..self.rows.len() {
-      for j in 0..self.cols.len() {
-        let cell = (i, j);
+  fn lay_out(grid: &mut Grid, w: i32, h: i32) {
+    grid.measure(MeasureSize {
+      w: Some(w),
+      h: Some(h),
+    });
 
-        match self.control_cells.get(&cell) {
-          Some(c) => for k in c {
-            let mut child = self.children[*k].borrow_mut();
+    grid.arrange(Rect {
+      pos: Point { x: 1, y: 1 },
+      size: Size { w, h },
+    });
+  }
 
-            child.measure(MeasureSize {
-              w: col_sizes.get(&j).map(|v| *v),
-              h: row_sizes.get(&j).map(|v| *v),
-            });
-          },
-          None => (),
-        }
-      }
-    }
+  #[test]
+  fn shares_by_weight() {
+    assert_eq!(share(10, &[1.0, 1.0]), vec![5, 5]);
+    assert_eq!(share(10, &[1.0, 4.0]), vec![2, 8]);
 
-    // TODO: this is wrong
-    MeasureSize {
-      w: Some(used_size.w),
-      h: Some(used_size.h),
-    }
+    // What's lost to rounding down goes to the biggest remainders first, and
+    // to the earliest of any that are tied
+    assert_eq!(share(5, &[1.0, 2.0]), vec![2, 3]);
+    assert_eq!(share(10, &[1.0, 1.0, 1.0]), vec![4, 3, 3]);
+    assert_eq!(share(2, &[1.0, 1.0, 1.0]), vec![1, 1, 0]);
   }
 
-  // NB: ignoring the size because it would probably require re-measuring
-  fn arrange_impl(&mut self, space: Rect) {
-    let mut row_pos: i32 = 0;
-    let mut col_pos: Vec<i32> = Vec::new();
+  #[test]
+  fn shares_nothing() {
+    assert_eq!(share(0, &[1.0, 1.0]), vec![0, 0]);
+    assert_eq!(share(-4, &[1.0, 1.0]), vec![0, 0]);
+    assert_eq!(share(4, &[0.0, 0.0]), vec![0, 0]);
+    assert_eq!(share(4, &[]), Vec::<i32>::new());
+  }
 
-    {
-      let mut pos: i32 = 0;
+  #[test]
+  fn sizes_content_tracks() {
+    let tracks: Vec<GridTrack> = vec![
+      GridLength::Content.into(),
+      GridLength::Static(2).into(),
+      GridLength::Content.into(),
+    ];
+
+    assert_eq!(track_sizes(&tracks, None, &[]), vec![0, 2, 0]);
+    assert_eq!(track_sizes(&tracks, None, &[(0, 1, 3)]), vec![3, 2, 0]);
+
+    // A child spanning tracks only adds what the ones it covers lack, and
+    // only to the content ones
+    assert_eq!(
+      track_sizes(&tracks, None, &[(0, 1, 3), (0, 3, 10)]),
+      vec![6, 2, 2]
+    );
+    assert_eq!(
+      track_sizes(&tracks, None, &[(0, 3, 10), (2, 3, 4)]),
+      vec![2, 2, 6]
+    );
+    assert_eq!(track_sizes(&tracks, None, &[(0, 3, 1)]), vec![0, 2, 0]);
+  }
 
-      for i in 0..self.cols.len() {
-        col_pos.push(pos);
-        pos = pos + self.col_sizes[i];
-      }
-    }
+  #[test]
+  fn clamps_content_tracks() {
+    let tracks = vec![
+      GridLength::Content.bounded(0, Some(2)),
+      GridLength::Content.bounded(3, None),
+    ];
 
-    for i in 0..self.rows.len() {
-      let row_size = self.row_sizes[i];
-
-      for j in 0..self.cols.len() {
-        let col_size = self.col_sizes[j];
-        let cell = (i, j);
-
-        match self.control_cells.get(&cell) {
-          Some(c) => for k in c {
-            let mut child = self.children[*k].borrow_mut();
-
-            child.arrange(Rect {
-              pos: Point {
-                x: space.pos.x + col_pos[j],
-                y: space.pos.y + row_pos,
-              },
-              size: Size {
-                w: col_size,
-                h: row_size,
-              },
-            })
-          },
-          None => (),
-        }
-      }
+    assert_eq!(track_sizes(&tracks, None, &[(0, 1, 5)]), vec![2, 3]);
+  }
 
-      row_pos = row_pos + row_size;
-    }
+  #[test]
+  fn shares_dynamic_tracks() {
+    let tracks: Vec<GridTrack> = vec![
+      GridLength::Dynamic(1.0).into(),
+      GridLength::Static(3).into(),
+      GridLength::Dynamic(2.0).into(),
+    ];
+
+    assert_eq!(track_sizes(&tracks, Some(12), &[]), vec![3, 3, 6]);
+    assert_eq!(track_sizes(&tracks, Some(2), &[]), vec![0, 3, 0]);
+    assert_eq!(track_sizes(&tracks, None, &[]), vec![0, 3, 0]);
   }
 
-  fn render_impl(&mut self) {
-    for child in &self.children {
-      let mut child = child.borrow_mut();
+  #[test]
+  fn clamps_dynamic_tracks() {
+    // Tracks held at their bounds leave the rest to the others
+    let tracks = vec![
+      GridLength::Dynamic(1.0).bounded(0, Some(2)),
+      GridLength::Dynamic(1.0).into(),
+      GridLength::Static(3).into(),
+    ];
 
-      child.render();
-    }
+    assert_eq!(track_sizes(&tracks, Some(13), &[]), vec![2, 8, 3]);
+
+    let tracks = vec![
+      GridLength::Dynamic(1.0).bounded(6, None),
+      GridLength::Dynamic(1.0).into(),
+    ];
+
+    assert_eq!(track_sizes(&tracks, Some(8), &[]), vec![6, 2]);
+    assert_eq!(track_sizes(&tracks, None, &[]), vec![6, 0]);
+  }
+
+  fn place(
+    h_align: GridAlign,
+    v_align: GridAlign,
+    w: i32,
+    h: i32,
+  ) -> (i32, i32, i32, i32) {
+    let child = probe(w, h);
+    let mut grid = Grid::new(
+      vec![(
+        el::add_ref(&child),
+        GridCell {
+          h_align,
+          v_align,
+          ..Default::default()
+        },
+      )],
+      vec![GridLength::Static(5)],
+      vec![GridLength::Static(10)],
+    );
+
+    lay_out(&mut grid, 10, 5);
+
+    let rect = child.borrow().rect.unwrap();
+    rect
+  }
+
+  #[test]
+  fn aligns_children() {
+    use self::GridAlign::*;
+
+    assert_eq!(place(Begin, Begin, 4, 2), (1, 1, 4, 2));
+    assert_eq!(place(Middle, Middle, 4, 2), (4, 2, 4, 2));
+    assert_eq!(place(End, End, 4, 2), (7, 4, 4, 2));
+    assert_eq!(place(Stretch, Stretch, 4, 2), (1, 1, 10, 5));
+    assert_eq!(place(End, Begin, 4, 2), (7, 1, 4, 2));
+
+    // Children never get more room than the cell has
+    assert_eq!(place(Middle, End, 12, 7), (1, 1, 10, 5));
+  }
+
+  #[test]
+  fn places_spanning_children() {
+    let wide = probe(1, 1);
+    let right = probe(1, 1);
+    let past = probe(1, 1);
+
+    let mut grid = Grid::new(
+      vec![
+        (
+          el::add_ref(&wide),
+          GridCell {
+            col_span: 2,
+            ..Default::default()
+          },
+        ),
+        (el::add_ref(&right), (1, 1).into()),
+        // Spans past the edge of the grid are cut short
+        (
+          el::add_ref(&past),
+          GridCell {
+            row: 1,
+            col: 0,
+            row_span: 3,
+            col_span: 5,
+            ..Default::default()
+          },
+        ),
+      ],
+      vec![GridLength::Static(2), GridLength::Content],
+      vec![GridLength::Static(3), GridLength::Dynamic(1.0)],
+    );
+
+    lay_out(&mut grid, 10, 5);
+
+    assert_eq!(wide.borrow().rect, Some((1, 1, 10, 2)));
+    assert_eq!(right.borrow().rect, Some((4, 3, 7, 1)));
+    assert_eq!(past.borrow().rect, Some((1, 3, 10, 1)));
   }
 }
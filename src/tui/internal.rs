@@ -1,4 +1,4 @@
-use tui::{core::*, element::ElementCoreData};
+use tui::{core::*, element::ElementCoreData, event::Event, surface::Surface};
 
 pub trait ElementCore {
   fn get_coredata(&self) -> &ElementCoreData;
@@ -9,7 +9,19 @@ pub trait ElementCore {
 
   fn arrange_impl(&mut self, Rect);
 
-  fn render_impl(&mut self);
+  fn render_impl(&mut self, &mut dyn Surface);
 
-  fn render_cur_impl(&mut self) {}
+  fn render_cur_impl(&mut self, _: &mut dyn Surface) {}
+
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    Some(event)
+  }
+
+  fn has_focus_impl(&self) -> bool {
+    false
+  }
+
+  fn move_focus_impl(&mut self, _: bool) -> bool {
+    false
+  }
 }
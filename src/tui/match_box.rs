@@ -1,4 +1,4 @@
-use nc;
+use std::cmp;
 use tui::prelude_internal::*;
 use word_list::WordlistForm;
 
@@ -6,57 +6,117 @@ pub enum MatchBoxStyle {
   Normal,
   Reveal,
   Highlight,
+  Hint,
 }
 
 pub struct MatchBox {
   coredata: ElementCoreData,
-  win: nc::WINDOW,
+  rect: Rect,
   form: WordlistForm,
   revealed: bool,
+  hinted: usize, // The number of letters revealed by hints
   style: MatchBoxStyle,
   reveal_pair: i32,
   hl_pair: i32,
+  hint_pair: i32,
+  finder_pair: Option<i32>, // In hot-seat play, the color of whoever found it
 }
 
 impl MatchBox {
-  pub fn new(form: WordlistForm, reveal_pair: i32, hl_pair: i32) -> Self {
+  pub fn new(
+    form: WordlistForm,
+    reveal_pair: i32,
+    hl_pair: i32,
+    hint_pair: i32,
+  ) -> Self {
     Self {
       coredata: Default::default(),
-      win: nc::newwin(1, 1, 0, 0),
+      rect: Default::default(),
       form,
       revealed: false,
+      hinted: 0,
       style: MatchBoxStyle::Normal,
       reveal_pair,
       hl_pair,
+      hint_pair,
+      finder_pair: None,
     }
   }
 
-  pub fn form(&self) -> &WordlistForm {
-    &self.form
-  }
-
   pub fn revealed(&self) -> bool {
     self.revealed
   }
 
   pub fn set_revealed(&mut self, val: bool) {
     self.revealed = val;
-    self.render();
   }
 
   pub fn set_style(&mut self, val: MatchBoxStyle) {
-    if self.revealed {
+    if self.revealed || self.hinted > 0 {
       self.style = val;
-      self.render();
     }
   }
 
-  fn displayed_str(&self) -> &str {
+  pub fn set_finder_pair(&mut self, val: Option<i32>) {
+    self.finder_pair = val;
+  }
+
+  // The number of blanks in the word
+  pub fn letters(&self) -> usize {
+    self.form.blanked.chars().filter(|c| *c == '_').count()
+  }
+
+  pub fn hinted(&self) -> usize {
+    self.hinted
+  }
+
+  // Reveals letters up to the given count, always leaving at least one blank.
+  // Returns whether anything new was revealed.
+  pub fn hint(&mut self, count: usize) -> bool {
+    let count = cmp::min(count, self.letters().saturating_sub(1));
+
+    if self.revealed || count <= self.hinted {
+      return false;
+    }
+
+    self.hinted = count;
+    self.set_style(MatchBoxStyle::Hint);
+
+    true
+  }
+
+  fn displayed_str(&self) -> String {
     if self.revealed {
-      &self.form.full
-    } else {
-      &self.form.blanked
+      return self.form.full.clone();
     }
+
+    let mut letter = 0;
+
+    self
+      .form
+      .blanked
+      .chars()
+      .zip(self.form.full.chars())
+      .map(|(blank, full)| {
+        if blank != '_' {
+          return blank;
+        }
+
+        letter = letter + 1;
+
+        if letter <= self.hinted {
+          full
+        } else {
+          blank
+        }
+      })
+      .collect()
+  }
+
+  // Revealed letters can be wider than the blanks they replace, so the box
+  // always takes up enough room for either
+  fn width(&self) -> i32 {
+    cmp::max(str_width(&self.form.blanked), str_width(&self.form.full)) as i32
   }
 }
 
@@ -71,35 +131,36 @@ impl ElementCore for MatchBox {
 
   fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
     MeasureSize {
-      w: Some(self.displayed_str().len() as i32),
+      w: Some(self.width()),
       h: Some(1),
     }
   }
 
   fn arrange_impl(&mut self, space: Rect) {
-    nc::wresize(self.win, 1, self.form.full.len() as i32);
-    nc::mvwin(self.win, space.pos.y, space.pos.x);
+    let w = self.width();
+
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size { w, h: 1 },
+    };
   }
 
-  fn render_impl(&mut self) {
-    use MatchBoxStyle::*;
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    use self::MatchBoxStyle::*;
 
     let pair = match self.style {
-      Normal => None,
-      Reveal => Some(self.reveal_pair),
+      Normal => self.finder_pair,
+      Reveal => Some(self.finder_pair.unwrap_or(self.reveal_pair)),
       Highlight => Some(self.hl_pair),
-    }.map(|p| nc::COLOR_PAIR(p as i16));
-
-    if let Some(pair) = pair {
-      nc::wattr_on(self.win, pair);
-    }
-
-    nc::mvwaddstr(self.win, 0, 0, self.displayed_str());
-
-    if let Some(pair) = pair {
-      nc::wattr_off(self.win, pair);
-    }
-
-    nc::wrefresh(self.win);
+      Hint => Some(self.hint_pair),
+    };
+    let text = self.displayed_str();
+
+    surface.erase();
+    surface.set_pair(pair);
+    surface.put_str(Point { x: 0, y: 0 }, &text);
+    surface.set_pair(None);
   }
 }
@@ -1,20 +1,36 @@
 pub mod core;
 pub mod element;
+pub mod event;
 pub mod internal;
+pub mod screen;
+pub mod surface;
+pub mod width;
 
+mod bonus_box;
+mod button_bar;
+mod clock_box;
+mod definition_box;
 mod grid;
 mod match_box;
+mod score_box;
+mod scoreboard;
+mod scroll_view;
 mod test_view;
+mod text_view;
 mod ui_root;
 mod word_box;
 mod wrap_box;
 
 pub mod prelude_internal {
-  pub use super::{core::*, element::*, internal::*};
+  pub use super::{
+    core::*, element::*, event::*, internal::*, surface::*, width::*,
+  };
 }
 
 pub mod controls {
   pub use super::{
-    grid::*, match_box::*, test_view::*, ui_root::*, word_box::*, wrap_box::*,
+    bonus_box::*, button_bar::*, clock_box::*, definition_box::*, grid::*,
+    match_box::*, score_box::*, scoreboard::*, scroll_view::*, test_view::*,
+    text_view::*, ui_root::*, word_box::*, wrap_box::*,
   };
 }
@@ -0,0 +1,107 @@
+use tui::prelude_internal::*;
+
+pub struct ScoreBox {
+  coredata: ElementCoreData,
+  rect: Rect,
+  points: u32,
+  found: usize,
+  total: usize,
+  hints: usize,
+  best: Option<u32>,
+  best_pair: i32,
+}
+
+impl ScoreBox {
+  pub fn new(total: usize, best: Option<u32>, best_pair: i32) -> Self {
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      points: 0,
+      found: 0,
+      total,
+      hints: 0,
+      best,
+      best_pair,
+    }
+  }
+
+  pub fn set_score(&mut self, points: u32, found: usize) {
+    self.points = points;
+    self.found = found;
+  }
+
+  pub fn set_hints(&mut self, val: usize) {
+    self.hints = val;
+  }
+
+  fn beat_best(&self) -> bool {
+    self.points > 0 && self.best.map_or(true, |b| self.points > b)
+  }
+
+  fn score_str(&self) -> String {
+    let mut ret = format!(
+      "score: {}  found: {}/{}",
+      self.points, self.found, self.total
+    );
+
+    if self.hints > 0 {
+      ret.push_str(&format!("  hints: {}", self.hints));
+    }
+
+    ret
+  }
+
+  fn best_str(&self) -> String {
+    match self.best {
+      Some(b) => format!("  best: {}", b),
+      None => String::new(),
+    }
+  }
+}
+
+impl ElementCore for ScoreBox {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    MeasureSize {
+      w: Some((self.score_str().len() + self.best_str().len()) as i32),
+      h: Some(1),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    // Take up the whole space so the text has room to grow
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size {
+        w: space.size.w,
+        h: 1,
+      },
+    };
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+
+    let pair = if self.beat_best() {
+      Some(self.best_pair)
+    } else {
+      None
+    };
+    let (score, best) = (self.score_str(), self.best_str());
+
+    surface.set_pair(pair);
+    surface.put_str(Point { x: 0, y: 0 }, &score);
+    surface.set_pair(None);
+
+    surface.add_str(&best);
+  }
+}
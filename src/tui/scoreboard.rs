@@ -0,0 +1,111 @@
+use tui::prelude_internal::*;
+
+// A single line with each player's points and words found, for hot-seat play.
+// The marked players (whoever's turn it is, or the winners once the round is
+// over) are flagged with the mark character.
+pub struct Scoreboard {
+  coredata: ElementCoreData,
+  rect: Rect,
+  players: Vec<(String, u32, usize)>, // (name, points, words found)
+  pairs: Vec<i32>,
+  marked: Vec<usize>,
+  mark: char,
+}
+
+impl Scoreboard {
+  pub fn new(pairs: Vec<i32>) -> Self {
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      players: Vec::new(),
+      pairs,
+      marked: Vec::new(),
+      mark: '>',
+    }
+  }
+
+  pub fn set_players(&mut self, players: Vec<(String, u32, usize)>) {
+    self.players = players;
+  }
+
+  pub fn set_turn(&mut self, turn: usize) {
+    self.marked = vec![turn];
+    self.mark = '>';
+  }
+
+  pub fn set_winners(&mut self, winners: Vec<usize>) {
+    self.marked = winners;
+    self.mark = '*';
+  }
+
+  fn entry_strs(&self) -> Vec<(String, String)> {
+    self
+      .players
+      .iter()
+      .enumerate()
+      .map(|(i, &(ref name, points, found))| {
+        let mark = if self.marked.contains(&i) {
+          self.mark
+        } else {
+          ' '
+        };
+
+        (
+          format!("{}{}", mark, name),
+          format!(" {} ({})", points, found),
+        )
+      })
+      .collect()
+  }
+}
+
+impl ElementCore for Scoreboard {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    let w: usize = self
+      .entry_strs()
+      .iter()
+      .map(|&(ref n, ref s)| str_width(n) + s.len() + 2)
+      .sum();
+
+    MeasureSize {
+      w: Some(w as i32),
+      h: Some(1),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size {
+        w: space.size.w.max(1),
+        h: 1,
+      },
+    };
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+    surface.move_cur(Point { x: 0, y: 0 });
+
+    for (i, (name, score)) in self.entry_strs().into_iter().enumerate() {
+      let pair = self.pairs.get(i).cloned();
+
+      surface.set_pair(pair);
+      surface.add_str(&name);
+      surface.set_pair(None);
+
+      surface.add_str(&score);
+      surface.add_str("  ");
+    }
+  }
+}
@@ -0,0 +1,33 @@
+use mouse;
+use nc;
+
+// The terminal, for as long as curses has it.  Dropping this gives it back, so
+// that an error returned (or a panic) partway through a round doesn't leave the
+// terminal unusable.
+pub struct Screen {
+  win: nc::WINDOW,
+}
+
+impl Screen {
+  pub fn init() -> Self {
+    let win = nc::initscr();
+    nc::start_color();
+    nc::use_default_colors();
+    nc::cbreak();
+    nc::noecho();
+    nc::keypad(win, true);
+    mouse::enable();
+
+    Self { win }
+  }
+
+  pub fn win(&self) -> nc::WINDOW {
+    self.win
+  }
+}
+
+impl Drop for Screen {
+  fn drop(&mut self) {
+    nc::endwin();
+  }
+}
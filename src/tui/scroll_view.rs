@@ -0,0 +1,255 @@
+use keymap::Action;
+use std::cmp;
+use tui::prelude_internal::*;
+
+const WHEEL_STEP: i32 = 3;
+
+pub enum ScrollAxis {
+  Horizontal,
+  Vertical,
+}
+
+// Shows as much of its child as fits, letting it be scrolled along one axis
+// with the page keys or the mouse wheel.  While the child doesn't fit, a bar
+// along the bottom (or right) edge shows which part of it is in view.
+pub struct ScrollView<'a> {
+  coredata: ElementCoreData,
+  child: ElemRef<'a>,
+  axis: ScrollAxis,
+  rect: Rect,
+  len: i32, // How long the child is along the axis
+  offset: i32,
+}
+
+impl ScrollAxis {
+  // Measures a child with the given room across the axis (and as much as it
+  // likes along it), giving its length along the axis and across it
+  fn measure(
+    &self,
+    child: &ElemRef,
+    across: Option<i32>,
+  ) -> (Option<i32>, Option<i32>) {
+    let mut child = child.borrow_mut();
+
+    child.measure(match self {
+      ScrollAxis::Horizontal => MeasureSize { w: None, h: across },
+      ScrollAxis::Vertical => MeasureSize { w: across, h: None },
+    });
+
+    let desired = child.desired_size();
+
+    match self {
+      ScrollAxis::Horizontal => (desired.w, desired.h),
+      ScrollAxis::Vertical => (desired.h, desired.w),
+    }
+  }
+}
+
+impl<'a> ScrollView<'a> {
+  pub fn new(child: ElemRef<'a>, axis: ScrollAxis) -> Self {
+    Self {
+      coredata: Default::default(),
+      child,
+      axis,
+      rect: Rect {
+        pos: Point { x: 0, y: 0 },
+        size: Size { w: 0, h: 0 },
+      },
+      len: 0,
+      offset: 0,
+    }
+  }
+
+  // The length of the view along the axis, and across it
+  fn view_len(&self) -> (i32, i32) {
+    match self.axis {
+      ScrollAxis::Horizontal => (self.rect.size.w, self.rect.size.h),
+      ScrollAxis::Vertical => (self.rect.size.h, self.rect.size.w),
+    }
+  }
+
+  fn overflows(&self) -> bool {
+    self.len > self.view_len().0
+  }
+
+  // The part of the screen the child shows in, leaving room for the bar
+  fn view(&self) -> Rect {
+    let bar = self.overflows() as i32;
+    let mut ret = self.rect;
+
+    match self.axis {
+      ScrollAxis::Horizontal => ret.size.h = cmp::max(0, ret.size.h - bar),
+      ScrollAxis::Vertical => ret.size.w = cmp::max(0, ret.size.w - bar),
+    }
+
+    ret
+  }
+
+  fn scroll(&mut self, by: i32) {
+    let max = cmp::max(0, self.len - self.view_len().0);
+    let offset = cmp::max(0, cmp::min(self.offset + by, max));
+
+    if offset != self.offset {
+      let rect = self.rect;
+
+      self.offset = offset;
+      self.arrange_impl(rect);
+    }
+  }
+
+  fn render_bar(&self, surface: &mut dyn Surface) {
+    let (len, across) = self.view_len();
+
+    if !self.overflows() || len <= 0 || across <= 0 {
+      return;
+    }
+
+    // The thumb is as long as the view is, relative to the child
+    let thumb_len = cmp::max(1, len * len / self.len);
+    let thumb_pos = cmp::min(len - thumb_len, self.offset * len / self.len);
+
+    for i in 0..len {
+      let thumb = i >= thumb_pos && i < thumb_pos + thumb_len;
+
+      match self.axis {
+        ScrollAxis::Horizontal => surface.put_str(
+          Point {
+            x: i,
+            y: across - 1,
+          },
+          if thumb { "=" } else { "-" },
+        ),
+        ScrollAxis::Vertical => surface.put_str(
+          Point {
+            x: across - 1,
+            y: i,
+          },
+          if thumb { "#" } else { "|" },
+        ),
+      }
+    }
+  }
+}
+
+impl<'a> ElementCore for ScrollView<'a> {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, space: MeasureSize) -> MeasureSize {
+    // The child can be as long as it likes along the axis, and if that's too
+    // long, it loses a line across it to the bar
+    let (along, across) = match self.axis {
+      ScrollAxis::Horizontal => (space.w, space.h),
+      ScrollAxis::Vertical => (space.h, space.w),
+    };
+
+    let (mut len, mut thickness) = self.axis.measure(&self.child, across);
+    let mut bar = 0;
+
+    if along.map_or(false, |a| len.unwrap_or(0) > a) {
+      let across = across.map(|a| cmp::max(0, a - 1));
+      let (l, t) = self.axis.measure(&self.child, across);
+
+      len = l;
+      thickness = t;
+      bar = 1;
+    }
+
+    self.len = len.unwrap_or(0);
+
+    let len = Some(along.map_or(self.len, |a| cmp::min(self.len, a)));
+    let thickness = thickness.map(|t| t + bar);
+
+    match self.axis {
+      ScrollAxis::Horizontal => MeasureSize {
+        w: len,
+        h: thickness,
+      },
+      ScrollAxis::Vertical => MeasureSize {
+        w: thickness,
+        h: len,
+      },
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.rect = space;
+
+    let view = self.view();
+    let max = cmp::max(0, self.len - self.view_len().0);
+    self.offset = cmp::max(0, cmp::min(self.offset, max));
+
+    // The child is laid out at its full length, with the part scrolled past
+    // hanging off the start of the view
+    let rect = match self.axis {
+      ScrollAxis::Horizontal => Rect {
+        pos: Point {
+          x: view.pos.x - self.offset,
+          y: view.pos.y,
+        },
+        size: Size {
+          w: cmp::max(self.len, view.size.w),
+          h: view.size.h,
+        },
+      },
+      ScrollAxis::Vertical => Rect {
+        pos: Point {
+          x: view.pos.x,
+          y: view.pos.y - self.offset,
+        },
+        size: Size {
+          w: view.size.w,
+          h: cmp::max(self.len, view.size.h),
+        },
+      },
+    };
+
+    self.child.borrow_mut().arrange(rect);
+  }
+
+  // The child is drawn through a clip, so that only the part of it in view
+  // shows
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    self.render_bar(&mut Region::new(surface, self.rect));
+
+    let mut view = Region::clip(surface, self.view());
+
+    self.child.borrow_mut().render(&mut view);
+  }
+
+  fn render_cur_impl(&mut self, surface: &mut dyn Surface) {
+    let mut view = Region::clip(surface, self.view());
+
+    self.child.borrow_mut().render_cur(&mut view);
+  }
+
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    let page = cmp::max(1, self.view_len().0);
+
+    match event {
+      Event::Action(Action::PageUp) if self.overflows() => self.scroll(-page),
+      Event::Action(Action::PageDown) if self.overflows() => self.scroll(page),
+      Event::Scroll(at, by) if self.overflows() && self.rect.contains(at) => {
+        self.scroll(by * WHEEL_STEP)
+      }
+      // Controls scrolled out of view can't be clicked
+      Event::Click(at) if !self.view().contains(at) => return Some(event),
+      _ => return self.child.borrow_mut().handle(event),
+    }
+
+    None
+  }
+
+  fn has_focus_impl(&self) -> bool {
+    self.child.borrow().has_focus()
+  }
+
+  fn move_focus_impl(&mut self, forward: bool) -> bool {
+    self.child.borrow_mut().move_focus(forward)
+  }
+}
@@ -0,0 +1,579 @@
+use nc;
+use tui::{core::*, width::*};
+
+// Something controls draw themselves onto: a window on the terminal, an
+// in-memory screen, or a part of either.  Positions are relative to the
+// surface, and text that runs off the right edge wraps onto the next line, as
+// it does with ncurses.
+pub trait Surface {
+  fn size(&self) -> Size;
+
+  // The part of the surface that actually ends up on the screen
+  fn shown(&self) -> Rect {
+    Rect {
+      pos: Point { x: 0, y: 0 },
+      size: self.size(),
+    }
+  }
+
+  fn erase(&mut self);
+
+  // Like erase(), but also has the whole screen redrawn on the next refresh
+  fn clear(&mut self);
+
+  fn move_cur(&mut self, Point);
+
+  // Draws text at the cursor, leaving the cursor just past it
+  fn add_str(&mut self, &str);
+
+  fn put_str(&mut self, at: Point, s: &str) {
+    self.move_cur(at);
+    self.add_str(s);
+  }
+
+  // Draws a vertical line of the given length down from a position
+  fn vline(&mut self, Point, i32);
+
+  // Sets the color pair of everything drawn from now on
+  fn set_pair(&mut self, Option<i32>);
+
+  // Readies what's been drawn to go on the screen, along with the cursor, at
+  // the next update()
+  fn refresh(&mut self) {}
+
+  // Puts everything refreshed since the last update on the screen at once
+  fn update(&mut self) {}
+}
+
+pub struct NcSurface {
+  win: nc::WINDOW,
+  pair: Option<i32>,
+}
+
+impl NcSurface {
+  pub fn new(win: nc::WINDOW) -> Self {
+    Self { win, pair: None }
+  }
+}
+
+impl Surface for NcSurface {
+  fn size(&self) -> Size {
+    let mut size = Size { w: 0, h: 0 };
+    nc::getmaxyx(self.win, &mut size.h, &mut size.w);
+
+    size
+  }
+
+  fn erase(&mut self) {
+    nc::werase(self.win);
+  }
+
+  fn clear(&mut self) {
+    nc::wclear(self.win);
+  }
+
+  fn move_cur(&mut self, to: Point) {
+    nc::wmove(self.win, to.y, to.x);
+  }
+
+  fn add_str(&mut self, s: &str) {
+    nc::waddstr(self.win, s);
+  }
+
+  fn vline(&mut self, at: Point, len: i32) {
+    nc::mvwvline(self.win, at.y, at.x, nc::ACS_VLINE(), len);
+  }
+
+  fn set_pair(&mut self, pair: Option<i32>) {
+    if let Some(old) = self.pair {
+      nc::wattr_off(self.win, nc::COLOR_PAIR(old as i16));
+    }
+
+    if let Some(new) = pair {
+      nc::wattr_on(self.win, nc::COLOR_PAIR(new as i16));
+    }
+
+    self.pair = pair;
+  }
+
+  fn refresh(&mut self) {
+    nc::wnoutrefresh(self.win);
+  }
+
+  fn update(&mut self) {
+    nc::doupdate();
+  }
+}
+
+// A part of another surface, which is what each control draws itself onto.
+// Anything drawn outside the part, or outside what's shown of the surface
+// underneath, is left out.
+pub struct Region<'a> {
+  parent: &'a mut dyn Surface,
+  pos: Point, // Where the part is on the parent
+  size: Size,
+  shown: Rect,
+  cur: Point,
+  pair: Option<i32>,
+}
+
+impl<'a> Region<'a> {
+  pub fn new(parent: &'a mut dyn Surface, rect: Rect) -> Self {
+    let shown = parent.shown().intersect(&rect);
+
+    Self {
+      parent,
+      pos: rect.pos,
+      size: rect.size,
+      shown: Rect {
+        pos: Point {
+          x: shown.pos.x - rect.pos.x,
+          y: shown.pos.y - rect.pos.y,
+        },
+        size: shown.size,
+      },
+      cur: Point { x: 0, y: 0 },
+      pair: None,
+    }
+  }
+
+  // The whole of a surface, showing only what's inside the clip (such as the
+  // view of a ScrollView, for its child)
+  pub fn clip(parent: &'a mut dyn Surface, clip: Rect) -> Self {
+    let size = parent.size();
+    let mut ret = Self::new(
+      parent,
+      Rect {
+        pos: Point { x: 0, y: 0 },
+        size,
+      },
+    );
+
+    ret.shown = ret.shown.intersect(&clip);
+    ret
+  }
+
+  fn on_parent(&self, at: Point) -> Point {
+    Point {
+      x: self.pos.x + at.x,
+      y: self.pos.y + at.y,
+    }
+  }
+}
+
+impl<'a> Surface for Region<'a> {
+  fn size(&self) -> Size {
+    self.size
+  }
+
+  fn shown(&self) -> Rect {
+    self.shown
+  }
+
+  fn erase(&mut self) {
+    let blank = " ".repeat(self.shown.size.w as usize);
+
+    self.parent.set_pair(None);
+
+    for y in 0..self.shown.size.h {
+      let at = self.on_parent(Point {
+        x: self.shown.pos.x,
+        y: self.shown.pos.y + y,
+      });
+
+      self.parent.put_str(at, &blank);
+    }
+  }
+
+  fn clear(&mut self) {
+    self.erase();
+  }
+
+  fn move_cur(&mut self, to: Point) {
+    self.cur = to;
+
+    if self.shown.contains(to) {
+      let at = self.on_parent(to);
+      self.parent.move_cur(at);
+    }
+  }
+
+  // Characters are drawn one at a time (along with any combining accents after
+  // them), and only if the whole character is shown
+  fn add_str(&mut self, s: &str) {
+    let mut chars = s.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+      let w = char_width(ch) as i32;
+
+      if w == 0 {
+        continue;
+      }
+
+      let mut cell = ch.to_string();
+
+      while let Some(&c) = chars.peek() {
+        if char_width(c) != 0 {
+          break;
+        }
+
+        cell.push(c);
+        chars.next();
+      }
+
+      if self.cur.x + w > self.size.w {
+        self.cur = Point {
+          x: 0,
+          y: self.cur.y + 1,
+        };
+      }
+
+      if self.cur.y >= self.size.h {
+        break;
+      }
+
+      let end = Point {
+        x: self.cur.x + w - 1,
+        y: self.cur.y,
+      };
+
+      if self.shown.contains(self.cur) && self.shown.contains(end) {
+        let at = self.on_parent(self.cur);
+
+        self.parent.set_pair(self.pair);
+        self.parent.put_str(at, &cell);
+      }
+
+      self.cur.x = self.cur.x + w;
+    }
+  }
+
+  fn vline(&mut self, at: Point, len: i32) {
+    for y in at.y..at.y + len {
+      let at = Point { x: at.x, y };
+
+      if self.shown.contains(at) {
+        let on_parent = self.on_parent(at);
+
+        self.parent.set_pair(self.pair);
+        self.parent.vline(on_parent, 1);
+      }
+    }
+  }
+
+  fn set_pair(&mut self, pair: Option<i32>) {
+    self.pair = pair;
+  }
+}
+
+#[cfg(test)]
+pub mod tests {
+  use super::*;
+  use std::{cell::RefCell, rc::Rc};
+  use tui::{controls::*, element as el};
+
+  #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+  pub struct Cell {
+    pub ch: char, // '\0' in the second column of a wide character
+    pub pair: Option<i32>,
+  }
+
+  static BLANK: Cell = Cell {
+    ch: ' ',
+    pair: None,
+  };
+
+  // A grid of character cells with a cursor, standing in for the terminal so
+  // that layouts can be drawn and checked without one
+  pub struct CellBuffer {
+    size: Size,
+    cells: Vec<Cell>,
+    cur: Point,
+  }
+
+  impl CellBuffer {
+    pub fn new(size: Size) -> Self {
+      let len = (size.w.max(0) * size.h.max(0)) as usize;
+
+      Self {
+        size,
+        cells: vec![BLANK; len],
+        cur: Point { x: 0, y: 0 },
+      }
+    }
+
+    pub fn size(&self) -> Size {
+      self.size
+    }
+
+    pub fn cursor(&self) -> Point {
+      self.cur
+    }
+
+    fn index(&self, at: Point) -> Option<usize> {
+      if at.x < 0 || at.y < 0 || at.x >= self.size.w || at.y >= self.size.h {
+        return None;
+      }
+
+      Some((at.y * self.size.w + at.x) as usize)
+    }
+
+    // The cell at the given position, or a blank one off the edge
+    pub fn cell(&self, at: Point) -> Cell {
+      self.index(at).map_or(BLANK, |i| self.cells[i])
+    }
+
+    fn set_cell(&mut self, at: Point, cell: Cell) {
+      if let Some(i) = self.index(at) {
+        self.cells[i] = cell;
+      }
+    }
+
+    // The text of each row, without trailing spaces
+    pub fn lines(&self) -> Vec<String> {
+      (0..self.size.h)
+        .map(|y| {
+          let line: String = (0..self.size.w)
+            .map(|x| self.cell(Point { x, y }).ch)
+            .filter(|c| *c != '\0')
+            .collect();
+
+          line.trim_end().to_string()
+        })
+        .collect()
+    }
+
+    fn erase(&mut self) {
+      for cell in &mut self.cells {
+        *cell = BLANK;
+      }
+    }
+
+    // Characters with no width of their own (such as combining accents) are
+    // left out
+    fn add_str(&mut self, s: &str, pair: Option<i32>) {
+      for ch in s.chars() {
+        let w = char_width(ch) as i32;
+
+        if w == 0 {
+          continue;
+        }
+
+        if self.cur.x + w > self.size.w {
+          self.cur = Point {
+            x: 0,
+            y: self.cur.y + 1,
+          };
+        }
+
+        if self.cur.y >= self.size.h {
+          break;
+        }
+
+        let cur = self.cur;
+        self.set_cell(cur, Cell { ch, pair });
+
+        for x in 1..w {
+          self.set_cell(
+            Point {
+              x: cur.x + x,
+              y: cur.y,
+            },
+            Cell { ch: '\0', pair },
+          );
+        }
+
+        self.cur.x = self.cur.x + w;
+      }
+    }
+  }
+
+  // The whole of a CellBuffer.  Like an ncurses window, it keeps what's drawn on
+  // it to itself until it's refreshed.
+  pub struct BufferSurface {
+    screen: Rc<RefCell<CellBuffer>>,
+    buf: CellBuffer,
+    pair: Option<i32>,
+  }
+
+  impl BufferSurface {
+    pub fn new(screen: Rc<RefCell<CellBuffer>>) -> Self {
+      let size = screen.borrow().size();
+
+      Self {
+        screen,
+        buf: CellBuffer::new(size),
+        pair: None,
+      }
+    }
+  }
+
+  impl Surface for BufferSurface {
+    fn size(&self) -> Size {
+      self.buf.size()
+    }
+
+    fn erase(&mut self) {
+      self.buf.erase();
+    }
+
+    fn clear(&mut self) {
+      self.buf.erase();
+    }
+
+    fn move_cur(&mut self, to: Point) {
+      self.buf.cur = to;
+    }
+
+    fn add_str(&mut self, s: &str) {
+      let pair = self.pair;
+      self.buf.add_str(s, pair);
+    }
+
+    fn vline(&mut self, at: Point, len: i32) {
+      let pair = self.pair;
+
+      for y in at.y..at.y + len {
+        self
+          .buf
+          .set_cell(Point { x: at.x, y }, Cell { ch: '│', pair });
+      }
+    }
+
+    fn set_pair(&mut self, pair: Option<i32>) {
+      self.pair = pair;
+    }
+
+    fn refresh(&mut self) {
+      let mut screen = self.screen.borrow_mut();
+
+      screen.cells = self.buf.cells.clone();
+      screen.cur = self.buf.cur;
+    }
+  }
+
+  // Lays out and draws an element on a screen of the given size
+  pub fn draw(w: i32, h: i32, child: el::ElemRef) -> Rc<RefCell<CellBuffer>> {
+    let screen = Rc::new(RefCell::new(CellBuffer::new(Size { w, h })));
+    let surface = BufferSurface::new(Rc::clone(&screen));
+    let mut ui_root = UiRoot::with_surface(Box::new(surface), child);
+
+    ui_root.resize();
+    ui_root.draw();
+
+    screen
+  }
+
+  pub fn text(lines: &[&str]) -> el::ElemRef<'static> {
+    el::wrap(TextView::new(lines.iter().map(|l| l.to_string()).collect()))
+  }
+
+  #[test]
+  fn wraps_text() {
+    let mut buf = CellBuffer::new(Size { w: 4, h: 3 });
+
+    buf.add_str("abcdef", None);
+
+    assert_eq!(buf.lines(), vec!["abcd", "ef", ""]);
+    assert_eq!(buf.cursor(), Point { x: 2, y: 1 });
+  }
+
+  // Text wraps at the edge of a region, and anything outside a clip is left out
+  #[test]
+  fn draws_regions() {
+    let screen = Rc::new(RefCell::new(CellBuffer::new(Size { w: 6, h: 3 })));
+    let mut surface = BufferSurface::new(Rc::clone(&screen));
+
+    Region::new(
+      &mut surface,
+      Rect {
+        pos: Point { x: 2, y: 0 },
+        size: Size { w: 3, h: 2 },
+      },
+    ).put_str(Point { x: 0, y: 0 }, "abcdef");
+
+    {
+      let mut clip = Region::clip(
+        &mut surface,
+        Rect {
+          pos: Point { x: 0, y: 2 },
+          size: Size { w: 4, h: 1 },
+        },
+      );
+
+      Region::new(
+        &mut clip,
+        Rect {
+          pos: Point { x: 2, y: 2 },
+          size: Size { w: 4, h: 1 },
+        },
+      ).put_str(Point { x: 0, y: 0 }, "ghij");
+    }
+
+    surface.refresh();
+
+    assert_eq!(screen.borrow().lines(), vec!["  abc", "  def", "  gh"]);
+  }
+
+  #[test]
+  fn draws_grid() {
+    let grid = el::wrap(Grid::new(
+      vec![
+        (text(&["one"]), (0, 0)),
+        (text(&["two"]), (0, 1)),
+        (text(&["three"]), (1, 0)),
+      ],
+      vec![GridLength::Content, GridLength::Content],
+      vec![GridLength::Content, GridLength::Dynamic(1.0)],
+    ));
+    let screen = draw(10, 3, grid);
+
+    assert_eq!(screen.borrow().lines(), vec!["one  two", "three", ""]);
+  }
+
+  #[test]
+  fn draws_wrap_box() {
+    let wrap_box = el::wrap(WrapBox::new(
+      vec![text(&["aa"]), text(&["bb"]), text(&["cc"])],
+      WrapMode::Rows,
+      WrapAlign::Begin,
+      WrapAlign::Begin,
+      1,
+      0,
+    ));
+    let screen = draw(5, 3, wrap_box);
+
+    assert_eq!(screen.borrow().lines(), vec!["aa bb", "cc", ""]);
+  }
+
+  #[test]
+  fn clips_scrolled_child() {
+    let scroll_view = el::wrap(ScrollView::new(
+      text(&["one", "two", "three"]),
+      ScrollAxis::Vertical,
+    ));
+    let screen = draw(4, 2, scroll_view);
+
+    // The last column goes to the scroll bar
+    assert_eq!(screen.borrow().lines(), vec!["one#", "two|"]);
+  }
+
+  #[test]
+  fn shows_cursor() {
+    let word_box = el::wrap(WordBox::new("tones".into(), 1, 2, 3));
+
+    word_box.borrow_mut().set_buf("to");
+
+    let grid = el::wrap(Grid::new(
+      vec![
+        (text(&["guess:"]), (0, 0)),
+        (word_box as el::ElemRef, (1, 0)),
+      ],
+      vec![GridLength::Content, GridLength::Content],
+      vec![GridLength::Dynamic(1.0)],
+    ));
+    let screen = draw(12, 2, grid);
+
+    assert_eq!(screen.borrow().cursor(), Point { x: 4, y: 1 });
+  }
+}
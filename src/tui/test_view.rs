@@ -7,12 +7,60 @@ pub struct TestView<'a> {
 }
 
 impl<'a> TestView<'a> {
-  pub fn new(word_box: ElemRef<'a>, match_box: ElemRef<'a>) -> Self {
+  pub fn new(
+    word_box: ElemRef<'a>,
+    match_box: ElemRef<'a>,
+    score_box: ElemRef<'a>,
+    clock_box: Option<ElemRef<'a>>,
+    bonus_box: ElemRef<'a>,
+    definition_box: Option<ElemRef<'a>>,
+    scoreboard: Option<ElemRef<'a>>,
+    buttons: Option<ElemRef<'a>>,
+  ) -> Self {
+    let status_bar = el::wrap(Grid::new(
+      Some((score_box, (0, 0)))
+        .into_iter()
+        .chain(clock_box.map(|c| (c, (0, 1)))),
+      vec![GridLength::Content],
+      vec![GridLength::Dynamic(1.0), GridLength::Content],
+    ));
+
+    // The definition panel, if any, sits to the right of the matches
+    let match_box = match definition_box {
+      Some(d) => el::wrap(Grid::new(
+        vec![(match_box, (0, 0)), (d, (0, 1))],
+        vec![GridLength::Dynamic(1.0)],
+        vec![GridLength::Dynamic(1.0), GridLength::Content],
+      )),
+      None => match_box,
+    };
+
+    // The scoreboard, if any, goes right under the status bar
+    let mut rows = vec![(status_bar as ElemRef, GridLength::Content)];
+
+    if let Some(s) = scoreboard {
+      rows.push((s, GridLength::Content));
+    }
+
+    rows.push((bonus_box, GridLength::Content));
+    rows.push((match_box, GridLength::Dynamic(1.0)));
+    rows.push((word_box.clone(), GridLength::Content));
+
+    if let Some(b) = buttons {
+      rows.push((b, GridLength::Content));
+    }
+
+    let (children, lengths): (Vec<_>, Vec<_>) = rows
+      .into_iter()
+      .enumerate()
+      .map(|(i, (child, len))| ((child, (i, 0)), len))
+      .unzip();
+
     Self {
       coredata: Default::default(),
       grid: el::wrap(Grid::new(
-        vec![(word_box.clone(), (1, 0)), (match_box, (0, 0))],
-        vec![GridLength::Dynamic(1.0), GridLength::Content],
+        children,
+        lengths,
         vec![GridLength::Dynamic(1.0)],
       )),
       word_box,
@@ -41,11 +89,23 @@ impl<'a> ElementCore for TestView<'a> {
     grid.arrange(space);
   }
 
-  fn render_impl(&mut self) {
-    let mut grid = self.grid.borrow_mut();
-    grid.render();
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    self.grid.borrow_mut().render(surface);
+  }
+
+  fn render_cur_impl(&mut self, surface: &mut dyn Surface) {
+    self.word_box.borrow_mut().render_cur(surface);
+  }
+
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    self.grid.borrow_mut().handle(event)
+  }
+
+  fn has_focus_impl(&self) -> bool {
+    self.grid.borrow().has_focus()
+  }
 
-    let mut word_box = self.word_box.borrow_mut();
-    word_box.render_cur();
+  fn move_focus_impl(&mut self, forward: bool) -> bool {
+    self.grid.borrow_mut().move_focus(forward)
   }
 }
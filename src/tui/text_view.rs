@@ -0,0 +1,64 @@
+use tui::prelude_internal::*;
+
+// A block of plain text, clipped to the space it's given
+pub struct TextView {
+  coredata: ElementCoreData,
+  rect: Rect,
+  lines: Vec<String>,
+}
+
+impl TextView {
+  pub fn new(lines: Vec<String>) -> Self {
+    Self {
+      coredata: Default::default(),
+      rect: Default::default(),
+      lines,
+    }
+  }
+
+  pub fn set_lines(&mut self, lines: Vec<String>) {
+    self.lines = lines;
+  }
+}
+
+impl ElementCore for TextView {
+  fn get_coredata(&self) -> &ElementCoreData {
+    &self.coredata
+  }
+
+  fn get_coredata_mut(&mut self) -> &mut ElementCoreData {
+    &mut self.coredata
+  }
+
+  fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
+    MeasureSize {
+      w: Some(
+        self
+          .lines
+          .iter()
+          .map(|l| l.chars().count())
+          .max()
+          .unwrap_or(0) as i32,
+      ),
+      h: Some(self.lines.len() as i32),
+    }
+  }
+
+  fn arrange_impl(&mut self, space: Rect) {
+    self.rect = space;
+  }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    surface.erase();
+
+    let size = self.rect.size;
+
+    for (i, line) in self.lines.iter().take(size.h as usize).enumerate() {
+      let line: String = line.chars().take(size.w as usize).collect();
+
+      surface.put_str(Point { x: 0, y: i as i32 }, &line);
+    }
+  }
+}
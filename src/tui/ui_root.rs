@@ -1,26 +1,103 @@
+use keymap::{Action, KeyCodes};
 use nc;
+use std::mem;
 use tui::prelude_internal::*;
 
 pub struct UiRoot<'a> {
-  win: nc::WINDOW,
+  surface: Box<Surface>,
   child: ElemRef<'a>,
 }
 
 impl<'a> UiRoot<'a> {
   pub fn new(win: nc::WINDOW, child: ElemRef<'a>) -> Self {
-    Self { win, child }
+    Self::with_surface(Box::new(NcSurface::new(win)), child)
   }
 
-  pub fn run(&mut self) {
-    // TODO
+  // Lays out controls on something other than the terminal, such as a
+  // BufferSurface
+  pub fn with_surface(surface: Box<Surface>, child: ElemRef<'a>) -> Self {
+    Self { surface, child }
   }
 
-  pub fn resize(&self) {
-    nc::wclear(self.win);
-    nc::wrefresh(self.win);
+  // Returns the old child.  Call resize() afterwards to lay out the new one.
+  pub fn set_child(&mut self, child: ElemRef<'a>) -> ElemRef<'a> {
+    mem::replace(&mut self.child, child)
+  }
+
+  // Reads and dispatches input until the given function, which is passed every
+  // event the elements don't handle themselves, returns something for the
+  // caller to act on (such as an action)
+  pub fn run<F, R>(&mut self, win: nc::WINDOW, keys: &KeyCodes, mut f: F) -> R
+  where
+    F: FnMut(Event) -> Option<R>,
+  {
+    loop {
+      if let Some(ret) = self
+        .read(win, keys)
+        .and_then(|e| self.dispatch(e))
+        .and_then(&mut f)
+      {
+        return ret;
+      }
+    }
+  }
+
+  // Waits for the next input without dispatching it, once the screen is up to
+  // date
+  pub fn read(&mut self, win: nc::WINDOW, keys: &KeyCodes) -> Option<Event> {
+    self.draw();
+
+    Event::read(win, keys)
+  }
+
+  // Draws everything afresh and puts it on the screen all at once.  Only what's
+  // changed since the last time is actually sent to the terminal.
+  pub fn draw(&mut self) {
+    let mut child = self.child.borrow_mut();
+
+    self.surface.erase();
+    child.render(&mut *self.surface);
+    child.render_cur(&mut *self.surface);
+
+    self.surface.refresh();
+    self.surface.update();
+  }
+
+  // Gives an event to the element with focus (or, for clicks and scrolling,
+  // whichever one is under the mouse).  Resizing and moving focus are taken
+  // care of here.
+  pub fn dispatch(&mut self, event: Event) -> Option<Event> {
+    match event {
+      Event::Resize => self.resize(),
+      Event::Action(Action::FocusNext) => self.move_focus(true),
+      Event::Action(Action::FocusPrev) => self.move_focus(false),
+      _ => {
+        let ret = self.child.borrow_mut().handle(event);
+
+        return match ret {
+          Some(e) if e != event => self.dispatch(e),
+          ret => ret,
+        };
+      }
+    }
+
+    None
+  }
+
+  fn move_focus(&mut self, forward: bool) {
+    let mut child = self.child.borrow_mut();
+
+    // Moving past the last element that takes focus wraps around to the first
+    if !child.move_focus(forward) {
+      child.move_focus(forward);
+    }
+  }
+
+  // Lays everything out again, to be drawn from scratch
+  pub fn resize(&mut self) {
+    self.surface.clear();
 
-    let mut size = Size { w: 0, h: 0 };
-    nc::getmaxyx(self.win, &mut size.h, &mut size.w);
+    let size = self.surface.size();
 
     let mut child = self.child.borrow_mut();
 
@@ -34,6 +111,8 @@ impl<'a> UiRoot<'a> {
       size,
     });
 
-    child.render();
+    if !child.has_focus() {
+      child.move_focus(true);
+    }
   }
 }
@@ -0,0 +1,21 @@
+use std::os::raw::c_int;
+
+// ncurses lays out wide characters by what the C library says their widths
+// are, so the same is used here to keep in step with it.  wchar_t is 32 bits
+// everywhere ncurses's wide API is.
+extern "C" {
+  fn wcwidth(c: u32) -> c_int;
+}
+
+// The number of columns a character takes up on screen.  Characters the
+// locale doesn't know how to print are counted as one column.
+pub fn char_width(c: char) -> usize {
+  match unsafe { wcwidth(c as u32) } {
+    w if w >= 0 => w as usize,
+    _ => 1,
+  }
+}
+
+pub fn str_width(s: &str) -> usize {
+  s.chars().map(char_width).sum()
+}
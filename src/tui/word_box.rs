@@ -1,20 +1,31 @@
-use markov::Markov;
-use nc;
-use std::{cmp, collections::HashMap};
+use keymap::{Action, Input};
+use std::cmp;
 use tui::prelude_internal::*;
 
+// Stands in for each letter not yet typed into an open box
+static OPEN_GHOST: char = '_';
+
+// The byte offset of the character at the given index, or the end of the string
+fn byte_pos(s: &str, i: usize) -> usize {
+  s.char_indices().nth(i).map_or(s.len(), |(b, _)| b)
+}
+
 pub struct WordBox {
   coredata: ElementCoreData,
-  win: nc::WINDOW,
-  cur: usize,
+  rect: Rect,
+  pos: Point,
+  cur: usize, // In characters, not bytes
   buf: String,
   ghost_buf: String,
   bad: bool,
   auto_sort: bool,
+  open: bool,
   key: String,
+  len: usize, // The number of letters in the key
   ghost_pair: i32,
   bad_ghost_pair: i32,
   auto_ghost_pair: i32,
+  focused: bool,
 }
 
 impl WordBox {
@@ -25,34 +36,56 @@ impl WordBox {
     auto_ghost_pair: i32,
   ) -> Self {
     let ghost_buf = key.clone();
+    let len = key.chars().count();
 
     Self {
       coredata: Default::default(),
-      win: nc::newwin(1, 1, 0, 0),
+      rect: Default::default(),
+      pos: Point { x: 0, y: 0 },
       cur: 0,
       buf: String::new(),
       ghost_buf,
       bad: false,
       auto_sort: false,
+      open: false,
       key,
+      len,
       ghost_pair,
       bad_ghost_pair,
       auto_ghost_pair,
+      focused: false,
     }
   }
 
+  // Makes a box that takes any letters, up to the given length, rather than
+  // only the letters of a key
+  pub fn open(
+    len: usize,
+    ghost_pair: i32,
+    bad_ghost_pair: i32,
+    auto_ghost_pair: i32,
+  ) -> Self {
+    let mut ret = Self::new(
+      OPEN_GHOST.to_string().repeat(len),
+      ghost_pair,
+      bad_ghost_pair,
+      auto_ghost_pair,
+    );
+
+    ret.open = true;
+    ret
+  }
+
   pub fn buf(&self) -> &String {
     &self.buf
   }
 
-  pub fn set_bad(&mut self, val: bool) {
-    if self.bad == val {
-      return;
-    }
+  fn buf_len(&self) -> usize {
+    self.buf.chars().count()
+  }
 
+  pub fn set_bad(&mut self, val: bool) {
     self.bad = val;
-
-    self.render();
   }
 
   pub fn auto_sort(&self) -> bool {
@@ -63,7 +96,6 @@ impl WordBox {
     self.auto_sort = val;
 
     self.fix_ghost();
-    self.render();
   }
 
   fn fix_ghost(&mut self) {
@@ -75,17 +107,31 @@ impl WordBox {
   }
 
   fn remove(&mut self, at: usize) {
-    self.ghost_buf.insert(0, self.buf.remove(at));
+    let ch = self.buf.remove(byte_pos(&self.buf, at));
+
+    self.ghost_buf.insert(0, if self.open { OPEN_GHOST } else { ch });
     self.fix_ghost();
   }
 
+  // Moves everything typed back into the ghost buffer
+  fn unput_all(&mut self) {
+    if self.open {
+      let ghost = OPEN_GHOST.to_string().repeat(self.buf_len());
+
+      self.ghost_buf.insert_str(0, &ghost);
+    } else {
+      self.ghost_buf.insert_str(0, &self.buf);
+    }
+
+    self.buf.clear();
+    self.cur = 0;
+  }
+
   fn del_empty(&mut self) {
     if self.auto_sort {
       self.auto_sort = false;
-      self.render();
     } else if self.bad {
       self.bad = false;
-      self.render();
     }
   }
 
@@ -97,10 +143,8 @@ impl WordBox {
         self.cur = self.cur - 1;
         let cur = self.cur;
         self.remove(cur);
-        self.render();
-      } else if self.buf.len() == 1 {
+      } else if self.buf_len() == 1 {
         self.remove(0);
-        self.render();
       }
     }
   }
@@ -109,10 +153,9 @@ impl WordBox {
     if self.buf.is_empty() {
       self.del_empty();
     } else {
-      if self.cur < self.buf.len() {
+      if self.cur < self.buf_len() {
         let cur = self.cur;
         self.remove(cur);
-        self.render();
       }
     }
   }
@@ -122,25 +165,25 @@ impl WordBox {
       // TODO: move this block elsewhere probably
       self.del_empty();
     }
-    self.ghost_buf.insert_str(0, &self.buf);
-    self.buf.clear();
-    self.cur = 0;
+    self.unput_all();
     self.fix_ghost();
-    self.render();
   }
 
-  pub fn put(&mut self, s: &str) {
+  // Moves letters from the ghost buffer into the buffer, returning whether any
+  // were available
+  fn put_chars(&mut self, s: &str) -> bool {
     let mut dirty = false;
 
     for c in s.chars() {
-      if self.buf.len() >= self.key.len() {
+      if self.buf_len() >= self.len {
         break;
       }
 
-      match self.ghost_buf.find(c) {
+      match self.ghost_buf.find(if self.open { OPEN_GHOST } else { c }) {
         Some(i) => {
           dirty = true;
-          self.buf.insert(self.cur, c);
+          let at = byte_pos(&self.buf, self.cur);
+          self.buf.insert(at, c);
           self.cur = self.cur + 1;
           self.ghost_buf.remove(i);
         }
@@ -148,15 +191,25 @@ impl WordBox {
       }
     }
 
-    if dirty {
+    dirty
+  }
+
+  pub fn put(&mut self, s: &str) {
+    if self.put_chars(s) {
       self.fix_ghost();
-      self.render();
     }
   }
 
+  // Replaces the contents of the buffer, subject to the same checks as put()
+  pub fn set_buf(&mut self, s: &str) {
+    self.unput_all();
+
+    self.put_chars(s);
+    self.fix_ghost();
+  }
+
   pub fn move_to(&mut self, to: usize) {
-    self.cur = cmp::max(0, cmp::min(self.buf.len(), to));
-    self.render_cur();
+    self.cur = cmp::max(0, cmp::min(self.buf_len(), to));
   }
 
   pub fn move_by(&mut self, by: isize) {
@@ -177,42 +230,77 @@ impl WordBox {
   }
 
   pub fn end(&mut self) {
-    let pos = self.buf.len();
+    let pos = self.buf_len();
     self.move_to(pos);
   }
 
-  pub fn shuffle(&mut self, markov: &Markov<char>) {
-    use std::collections::hash_map::Entry::*;
+  // Types the ghost letter or takes back the typed letter at the given screen
+  // position, returning whether there was one
+  fn click(&mut self, at: Point) -> bool {
+    let x = at.x - self.pos.x;
 
-    self.auto_sort = false;
+    if at.y != self.pos.y {
+      return false;
+    }
 
-    let mut remain: HashMap<char, usize> = HashMap::new();
+    let i = match self
+      .columns()
+      .iter()
+      .position(|&(col, c)| x >= col && x < col + char_width(c) as i32)
+    {
+      Some(i) => i,
+      None => return false,
+    };
+    let buf_len = self.buf_len();
 
-    for chr in self.ghost_buf.chars() {
-      match remain.entry(chr) {
-        Vacant(v) => {
-          v.insert(1);
-        }
-        Occupied(o) => {
-          let o = o.into_mut();
-          *o = *o + 1;
-        }
+    if i < buf_len {
+      self.remove(i);
+
+      if self.cur > i {
+        self.cur = self.cur - 1;
+      }
+
+      return true;
+    }
+
+    match self.ghost_buf.chars().nth(i - buf_len) {
+      Some(ch) if !self.open => {
+        self.put(&ch.to_string());
+        true
       }
+      _ => false,
     }
+  }
 
-    // TODO: keep an eye on this, it doesn't seem correct
-    self.ghost_buf = markov
-      .iter_counted(
-        markov
-          .rand_seed()
-          .filter(|c| self.ghost_buf.contains(**c))
-          .next()
-          .unwrap(),
-        remain,
-      )
-      .collect();
+  // The screen column of each letter, typed letters first.  Letters are a
+  // space apart, and can be more than one column wide.
+  fn columns(&self) -> Vec<(i32, char)> {
+    let mut x = 0;
+
+    self
+      .buf
+      .chars()
+      .chain(self.ghost_buf.chars())
+      .map(|c| {
+        let col = x;
+        x = x + char_width(c) as i32 + 1;
+        (col, c)
+      })
+      .collect()
+  }
+
+  fn width(&self) -> i32 {
+    (str_width(&self.key) + self.len) as i32 - 1
+  }
+
+  // Reorders the letters not yet typed with the given function
+  pub fn shuffle<F>(&mut self, f: F)
+  where
+    F: FnOnce(&str) -> String,
+  {
+    self.auto_sort = false;
 
-    self.render();
+    self.ghost_buf = f(&self.ghost_buf);
   }
 }
 
@@ -227,22 +315,37 @@ impl ElementCore for WordBox {
 
   fn measure_impl(&mut self, _: MeasureSize) -> MeasureSize {
     MeasureSize {
-      w: Some(self.key.len() as i32 * 2 + 1),
+      w: Some(self.width() + 2),
       h: Some(1),
     }
   }
 
+  // Letters typed into an open box can be wider than the blanks they replace,
+  // so the box takes all the space it's given
   fn arrange_impl(&mut self, space: Rect) {
-    nc::wresize(self.win, 1, self.key.len() as i32 * 2 - 1);
-    nc::mvwin(self.win, space.pos.y, space.pos.x);
+    self.pos = space.pos;
+
+    let w = cmp::max(self.width() + 1, space.size.w);
+
+    self.rect = Rect {
+      pos: space.pos,
+      size: Size { w, h: 1 },
+    };
   }
 
-  fn render_impl(&mut self) {
-    for (i, ch) in self.buf.char_indices() {
-      nc::mvwaddch(self.win, 0, (i * 2) as i32, ch as u32);
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    let columns = self.columns();
+    let buf_len = self.buf_len();
+
+    surface.erase();
+
+    for &(x, ch) in &columns[..buf_len] {
+      surface.put_str(Point { x, y: 0 }, &ch.to_string());
     }
 
-    let pair = nc::COLOR_PAIR(if self.auto_sort {
+    let pair = if self.auto_sort {
       self.auto_ghost_pair
     } else {
       if self.bad {
@@ -250,25 +353,61 @@ impl ElementCore for WordBox {
       } else {
         self.ghost_pair
       }
-    } as i16);
+    };
 
-    nc::wattr_on(self.win, pair);
+    surface.set_pair(Some(pair));
 
-    let buf_len = self.buf.len();
-
-    for (i, ch) in self.ghost_buf.char_indices() {
-      nc::mvwaddch(self.win, 0, ((i + buf_len) * 2) as i32, ch as u32);
+    for &(x, ch) in &columns[buf_len..] {
+      surface.put_str(Point { x, y: 0 }, &ch.to_string());
     }
 
-    nc::wattr_off(self.win, pair);
+    surface.set_pair(None);
+  }
+
+  fn render_cur_impl(&mut self, surface: &mut dyn Surface) {
+    let mut surface = Region::new(surface, self.rect);
+
+    let x: usize = self
+      .buf
+      .chars()
+      .take(self.cur)
+      .map(|c| char_width(c) + 1)
+      .sum();
 
-    nc::wrefresh(self.win);
+    surface.move_cur(Point { x: x as i32, y: 0 });
+  }
+
+  // Typing goes here, along with the keys for editing what's been typed
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    match event {
+      Event::Click(at) => {
+        if !self.click(at) {
+          return Some(event);
+        }
+      }
+      Event::Key(Input::Char(ch)) if ch.is_alphabetic() => {
+        let s = ch.to_lowercase().to_string();
+        self.put(&s);
+      }
+      Event::Action(Action::Clear) => self.clear(),
+      Event::Action(Action::DelLeft) => self.del_left(),
+      Event::Action(Action::DelRight) => self.del_right(),
+      Event::Action(Action::Left) => self.left(),
+      Event::Action(Action::Right) => self.right(),
+      Event::Action(Action::Home) => self.home(),
+      Event::Action(Action::End) => self.end(),
+      _ => return Some(event),
+    }
+
+    None
+  }
 
-    self.render_cur();
+  fn has_focus_impl(&self) -> bool {
+    self.focused
   }
 
-  fn render_cur_impl(&mut self) {
-    nc::wmove(self.win, 0, (self.cur * 2) as i32);
-    nc::wrefresh(self.win);
+  fn move_focus_impl(&mut self, _: bool) -> bool {
+    self.focused = !self.focused;
+    self.focused
   }
 }
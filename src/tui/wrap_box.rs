@@ -1,6 +1,10 @@
 use std::cmp;
 use tui::prelude_internal::*;
 
+// Rows lays children out left to right, wrapping onto new rows downwards; Cols
+// lays them out top to bottom, wrapping onto new columns to the right.  The
+// direction children run in is the main axis, and the one lines stack in is
+// the cross axis.
 pub enum WrapMode {
   Rows,
   Cols,
@@ -10,23 +14,88 @@ pub enum WrapAlign {
   Begin,
   Middle,
   End,
-  Stretch,
+  Stretch, // Share any space left over between the children (or lines)
 }
 
+struct Line {
+  len: i32, // Along the main axis, including spacing between children
+  thickness: i32,
+  count: usize,
+}
+
+// Children are aligned within their line by align, and the lines within the
+// box by cross_align.  Every child fills the thickness of its line.
 pub struct WrapBox<'a> {
   coredata: ElementCoreData,
-  lines: Vec<(Size, usize)>,
+  lines: Vec<Line>,
+  lens: Vec<i32>, // The length each child asked for along the main axis
   children: Vec<ElemRef<'a>>,
   mode: WrapMode,
   align: WrapAlign,
+  cross_align: WrapAlign,
+  item_sep: i32,
   line_sep: i32,
 }
 
+impl WrapMode {
+  fn split(&self, size: Size) -> (i32, i32) {
+    match self {
+      WrapMode::Rows => (size.w, size.h),
+      WrapMode::Cols => (size.h, size.w),
+    }
+  }
+
+  fn split_space(&self, space: MeasureSize) -> (Option<i32>, Option<i32>) {
+    match self {
+      WrapMode::Rows => (space.w, space.h),
+      WrapMode::Cols => (space.h, space.w),
+    }
+  }
+
+  fn size(&self, main: i32, cross: i32) -> Size {
+    match self {
+      WrapMode::Rows => Size { w: main, h: cross },
+      WrapMode::Cols => Size { w: cross, h: main },
+    }
+  }
+
+  fn point(&self, main: i32, cross: i32) -> Point {
+    match self {
+      WrapMode::Rows => Point { x: main, y: cross },
+      WrapMode::Cols => Point { x: cross, y: main },
+    }
+  }
+}
+
+impl WrapAlign {
+  // Where to start, and how much to add to each of count things, to fit them
+  // into extra free space
+  fn place(&self, extra: i32, count: usize) -> (i32, Vec<i32>) {
+    use self::WrapAlign::*;
+
+    let extra = cmp::max(0, extra);
+
+    match self {
+      Begin => (0, vec![0; count]),
+      Middle => (extra / 2, vec![0; count]),
+      End => (extra, vec![0; count]),
+      Stretch => (
+        0,
+        (0..count as i32)
+          .map(|i| extra / count as i32 + (i < extra % count as i32) as i32)
+          .collect(),
+      ),
+    }
+  }
+}
+
 impl<'a> WrapBox<'a> {
   pub fn new<IC>(
     children: IC,
     mode: WrapMode,
     align: WrapAlign,
+    cross_align: WrapAlign,
+    item_sep: i32,
     line_sep: i32,
   ) -> Self
   where
@@ -35,12 +104,49 @@ impl<'a> WrapBox<'a> {
     Self {
       coredata: Default::default(),
       lines: Vec::new(),
+      lens: Vec::new(),
       children: children.into_iter().collect(),
       mode,
       align,
+      cross_align,
+      item_sep,
       line_sep,
     }
   }
+
+  // The space each child takes up, relative to the box, when the box is given
+  // the size provided
+  fn layout(&self, size: Size) -> Vec<Rect> {
+    let (main, cross) = self.mode.split(size);
+    let lines_len = self.lines.iter().map(|l| l.thickness).sum::<i32>()
+      + self.line_sep * cmp::max(0, self.lines.len() as i32 - 1);
+
+    let (mut line_pos, line_extra) =
+      self.cross_align.place(cross - lines_len, self.lines.len());
+
+    let mut ret = Vec::with_capacity(self.children.len());
+    let mut lens = self.lens.iter();
+
+    for (line, line_extra) in self.lines.iter().zip(line_extra) {
+      let thickness = line.thickness + line_extra;
+      let (mut pos, extra) = self.align.place(main - line.len, line.count);
+
+      for extra in extra {
+        let len = lens.next().unwrap() + extra;
+
+        ret.push(Rect {
+          pos: self.mode.point(pos, line_pos),
+          size: self.mode.size(len, thickness),
+        });
+
+        pos = pos + len + self.item_sep;
+      }
+
+      line_pos = line_pos + thickness + self.line_sep;
+    }
+
+    ret
+  }
 }
 
 impl<'a> ElementCore for WrapBox<'a> {
@@ -53,216 +159,272 @@ impl<'a> ElementCore for WrapBox<'a> {
   }
 
   fn measure_impl(&mut self, space: MeasureSize) -> MeasureSize {
-    use WrapAlign::*;
-    use WrapMode::*;
+    let (main_space, cross_space) = self.mode.split_space(space);
 
     self.lines.clear();
+    self.lens.clear();
 
-    match self.mode {
-      Rows => {
-        let mut row_width = 0;
-        let mut row_max_height = 0;
-        let mut max_width = 0;
-        let mut height = 0;
-        let mut width_left = space.w;
-        let mut count = 0;
-
-        for child in &self.children {
-          let mut child = child.borrow_mut();
-
-          child.measure(MeasureSize {
-            w: Some(0),
-            h: Some(0),
-          });
-
-          let child_size = child.desired_size();
-          let child_w = child_size.w.unwrap_or(0);
-
-          if width_left.map_or(false, |w| child_w > w) {
-            self.lines.push((
-              Size {
-                w: row_width,
-                h: row_max_height,
-              },
-              count,
-            ));
-
-            max_width = cmp::max(max_width, row_width);
-            height = height + row_max_height + self.line_sep;
-
-            row_width = 0;
-            row_max_height = 0;
-            width_left = space.w;
-            count = 0;
-          }
-
-          row_width = row_width + child_w;
-          width_left = width_left.map(|w| w - child_w);
-          row_max_height = cmp::max(row_max_height, child_size.h.unwrap_or(0));
-          count = count + 1;
-        }
-
-        if count > 0 {
-          self.lines.push((
-            Size {
-              w: row_width,
-              h: row_max_height,
-            },
-            count,
-          ));
-        }
-
-        // TODO: re-measure children if align == Stretch
-
-        MeasureSize {
-          w: Some(max_width),
-          h: Some(cmp::max(0, height - self.line_sep)),
-        }
+    let mut line = Line {
+      len: 0,
+      thickness: 0,
+      count: 0,
+    };
+
+    for child in &self.children {
+      let mut child = child.borrow_mut();
+
+      child.measure(MeasureSize {
+        w: Some(0),
+        h: Some(0),
+      });
+
+      let desired = child.desired_size();
+      let (len, thickness) = self.mode.split(Size {
+        w: desired.w.unwrap_or(0),
+        h: desired.h.unwrap_or(0),
+      });
+
+      if line.count > 0
+        && main_space.map_or(false, |m| line.len + self.item_sep + len > m)
+      {
+        self.lines.push(line);
+
+        line = Line {
+          len: 0,
+          thickness: 0,
+          count: 0,
+        };
       }
-      Cols => {
-        let mut col_height = 0;
-        let mut col_max_width = 0;
-        let mut max_height = 0;
-        let mut width = 0;
-        let mut height_left = space.h;
-        let mut count = 0;
-
-        for child in &self.children {
-          let mut child = child.borrow_mut();
-
-          child.measure(MeasureSize {
-            w: Some(0),
-            h: Some(0),
-          });
-
-          let child_size = child.desired_size();
-          let child_h = child_size.h.unwrap_or(0);
-
-          if height_left.map_or(false, |h| child_h > h) {
-            self.lines.push((
-              Size {
-                w: col_max_width,
-                h: col_height,
-              },
-              count,
-            ));
-
-            max_height = cmp::max(max_height, col_height);
-            width = width + col_max_width + self.line_sep;
-
-            col_height = 0;
-            col_max_width = 0;
-            height_left = space.h;
-            count = 0;
-          }
-
-          col_height = col_height + child_h;
-          height_left = height_left.map(|h| h - child_h);
-          col_max_width = cmp::max(col_max_width, child_size.w.unwrap_or(0));
-          count = count + 1;
-        }
-
-        if count > 0 {
-          self.lines.push((
-            Size {
-              w: col_max_width,
-              h: col_height,
-            },
-            count,
-          ));
-        }
-
-        // TODO: re-measure children if align == Stretch
-
-        MeasureSize {
-          w: Some(cmp::max(0, width - self.line_sep)),
-          h: Some(max_height),
-        }
+
+      if line.count > 0 {
+        line.len = line.len + self.item_sep;
       }
+
+      line.len = line.len + len;
+      line.thickness = cmp::max(line.thickness, thickness);
+      line.count = line.count + 1;
+
+      self.lens.push(len);
     }
-  }
 
-  fn arrange_impl(&mut self, space: Rect) {
-    use WrapAlign::*;
-    use WrapMode::*;
-
-    match self.mode {
-      Rows => {
-        let mut i: usize = 0;
-        let mut row = (Size { w: 0, h: 0 }, 0);
-        let mut pos = Point {
-          x: 0,
-          y: -self.line_sep,
-        };
+    if line.count > 0 {
+      self.lines.push(line);
+    }
 
-        for child in &self.children {
-          if row.1 == 0 {
-            pos.y = pos.y + row.0.h + self.line_sep;
-            row = self.lines[i];
-            i = i + 1;
-            pos.x = 0; // TODO: pay attention to alignment when doing this
-          }
-
-          let mut child = child.borrow_mut();
-
-          let child_w = child.desired_size().w.unwrap_or(0);
-
-          child.arrange(Rect {
-            pos: Point {
-              x: pos.x + space.pos.x,
-              y: pos.y + space.pos.y,
-            },
-            size: Size {
-              w: child_w,
-              h: row.0.h,
-            },
-          });
-
-          pos.x = pos.x + child_w;
-          row.1 = row.1 - 1;
-        }
+    let main = self.lines.iter().map(|l| l.len).max().unwrap_or(0);
+    let cross = self.lines.iter().map(|l| l.thickness).sum::<i32>()
+      + self.line_sep * cmp::max(0, self.lines.len() as i32 - 1);
+
+    // Stretched children end up bigger than they asked to be, so let them
+    // know what they'll get
+    let stretch = |a: &WrapAlign, s: Option<i32>| match a {
+      WrapAlign::Stretch => s.is_some(),
+      _ => false,
+    };
+
+    if stretch(&self.align, main_space)
+      || stretch(&self.cross_align, cross_space)
+    {
+      let size = self.mode.size(
+        cmp::max(main, main_space.unwrap_or(0)),
+        cmp::max(cross, cross_space.unwrap_or(0)),
+      );
+
+      for (child, rect) in self.children.iter().zip(self.layout(size)) {
+        child.borrow_mut().measure(MeasureSize {
+          w: Some(rect.size.w),
+          h: Some(rect.size.h),
+        });
       }
-      Cols => {
-        let mut i: usize = 0;
-        let mut col = (Size { w: 0, h: 0 }, 0);
-        let mut pos = Point {
-          x: -self.line_sep,
-          y: 0,
-        };
+    }
 
-        for child in &self.children {
-          if col.1 == 0 {
-            pos.x = pos.x + col.0.w + self.line_sep;
-            col = self.lines[i];
-            i = i + 1;
-            pos.y = 0; // TODO: pay attention to alignment when doing this
-          }
-
-          let mut child = child.borrow_mut();
-
-          let child_h = child.desired_size().h.unwrap_or(0);
-
-          child.arrange(Rect {
-            pos: Point {
-              x: pos.x + space.pos.x,
-              y: pos.y + space.pos.y,
-            },
-            size: Size {
-              w: col.0.w,
-              h: child_h,
-            },
-          });
-
-          pos.y = pos.y + child_h;
-          col.1 = col.1 - 1;
-        }
-      }
+    let size = self.mode.size(main, cross);
+
+    MeasureSize {
+      w: Some(size.w),
+      h: Some(size.h),
     }
   }
 
-  fn render_impl(&mut self) {
-    for child in &self.children {
-      child.borrow_mut().render();
+  fn arrange_impl(&mut self, space: Rect) {
+    for (child, rect) in self.children.iter().zip(self.layout(space.size)) {
+      child.borrow_mut().arrange(Rect {
+        pos: Point {
+          x: space.pos.x + rect.pos.x,
+          y: space.pos.y + rect.pos.y,
+        },
+        size: rect.size,
+      });
     }
   }
+
+  fn render_impl(&mut self, surface: &mut dyn Surface) {
+    render_children(&self.children, surface);
+  }
+
+  fn render_cur_impl(&mut self, surface: &mut dyn Surface) {
+    render_children_cur(&self.children, surface);
+  }
+
+  fn handle_impl(&mut self, event: Event) -> Option<Event> {
+    handle_children(&self.children, event)
+  }
+
+  fn has_focus_impl(&self) -> bool {
+    children_have_focus(&self.children)
+  }
+
+  fn move_focus_impl(&mut self, forward: bool) -> bool {
+    move_children_focus(&self.children, forward)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tui::{
+    element as el,
+    surface::tests::{draw, text},
+  };
+
+  fn draw_box(
+    (w, h): (i32, i32),
+    words: &[&str],
+    mode: WrapMode,
+    align: WrapAlign,
+    cross_align: WrapAlign,
+    (item_sep, line_sep): (i32, i32),
+  ) -> Vec<String> {
+    let wrap_box = el::wrap(WrapBox::new(
+      words.iter().map(|w| text(&[w])),
+      mode,
+      align,
+      cross_align,
+      item_sep,
+      line_sep,
+    ));
+    let screen = draw(w, h, wrap_box);
+
+    let lines = screen.borrow().lines();
+    lines
+  }
+
+  fn rows(w: i32, align: WrapAlign) -> Vec<String> {
+    draw_box(
+      (w, 1),
+      &["aa", "bb", "cc"],
+      WrapMode::Rows,
+      align,
+      WrapAlign::Begin,
+      (1, 0),
+    )
+  }
+
+  fn row_lines(h: i32, cross_align: WrapAlign) -> Vec<String> {
+    draw_box(
+      (5, h),
+      &["aa", "bb", "cc"],
+      WrapMode::Rows,
+      WrapAlign::Begin,
+      cross_align,
+      (1, 1),
+    )
+  }
+
+  fn cols(align: WrapAlign) -> Vec<String> {
+    draw_box(
+      (5, 4),
+      &["a", "b", "c"],
+      WrapMode::Cols,
+      align,
+      WrapAlign::Begin,
+      (1, 1),
+    )
+  }
+
+  fn col_lines(cross_align: WrapAlign) -> Vec<String> {
+    draw_box(
+      (6, 4),
+      &["a", "b", "c"],
+      WrapMode::Cols,
+      WrapAlign::Begin,
+      cross_align,
+      (1, 1),
+    )
+  }
+
+  #[test]
+  fn aligns_rows() {
+    assert_eq!(rows(10, WrapAlign::Begin), vec!["aa bb cc"]);
+    assert_eq!(rows(10, WrapAlign::Middle), vec![" aa bb cc"]);
+    assert_eq!(rows(10, WrapAlign::End), vec!["  aa bb cc"]);
+    assert_eq!(rows(10, WrapAlign::Stretch), vec!["aa  bb  cc"]);
+  }
+
+  #[test]
+  fn stretches_remainder_to_first() {
+    // 5 spare columns between 3 children go 2, 2, 1
+    assert_eq!(rows(13, WrapAlign::Stretch), vec!["aa   bb   cc"]);
+  }
+
+  #[test]
+  fn aligns_lines_of_rows() {
+    assert_eq!(
+      row_lines(5, WrapAlign::Begin),
+      vec!["aa bb", "", "cc", "", ""]
+    );
+    assert_eq!(
+      row_lines(5, WrapAlign::Middle),
+      vec!["", "aa bb", "", "cc", ""]
+    );
+    assert_eq!(
+      row_lines(5, WrapAlign::End),
+      vec!["", "", "aa bb", "", "cc"]
+    );
+    assert_eq!(
+      row_lines(5, WrapAlign::Stretch),
+      vec!["aa bb", "", "", "cc", ""]
+    );
+    assert_eq!(
+      row_lines(6, WrapAlign::Stretch),
+      vec!["aa bb", "", "", "", "cc", ""]
+    );
+  }
+
+  #[test]
+  fn aligns_cols() {
+    assert_eq!(cols(WrapAlign::Begin), vec!["a c", "", "b", ""]);
+    assert_eq!(cols(WrapAlign::Middle), vec!["a", "  c", "b", ""]);
+    assert_eq!(cols(WrapAlign::End), vec!["", "a", "", "b c"]);
+    assert_eq!(cols(WrapAlign::Stretch), vec!["a c", "", "", "b"]);
+  }
+
+  #[test]
+  fn aligns_lines_of_cols() {
+    assert_eq!(col_lines(WrapAlign::Begin), vec!["a c", "", "b", ""]);
+    assert_eq!(col_lines(WrapAlign::Middle), vec![" a c", "", " b", ""]);
+    assert_eq!(col_lines(WrapAlign::End), vec!["   a c", "", "   b", ""]);
+    assert_eq!(col_lines(WrapAlign::Stretch), vec!["a   c", "", "b", ""]);
+  }
+
+  #[test]
+  fn measures_wrapped_lines() {
+    let mut wrap_box = WrapBox::new(
+      vec![text(&["aa"]), text(&["bb"]), text(&["cc"])],
+      WrapMode::Rows,
+      WrapAlign::Begin,
+      WrapAlign::Begin,
+      1,
+      1,
+    );
+
+    wrap_box.measure(MeasureSize {
+      w: Some(6),
+      h: None,
+    });
+
+    let desired = wrap_box.desired_size();
+
+    assert_eq!((desired.w, desired.h), (Some(5), Some(3)));
+  }
 }
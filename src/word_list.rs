@@ -1,5 +1,7 @@
+use difficulty::Difficulty;
 use diesel::{prelude::*, sqlite::SqliteConnection};
 use models::*;
+use std::collections::HashMap;
 
 pub struct WordList {
   conn: SqliteConnection,
@@ -48,12 +50,82 @@ impl WordList {
       .collect()
   }
 
-  pub fn get_set_keys(&self, len_key: &usize) -> Vec<String> {
+  pub fn all_words(&self) -> Vec<String> {
+    use schema::form_ids::dsl::*;
+
+    form_ids
+      .select(norm)
+      .order(norm.asc())
+      .load(&self.conn)
+      .unwrap()
+  }
+
+  // Checks against every word in the dictionary, not just ones in a set
+  pub fn is_word(&self, key: &str) -> bool {
+    use diesel::dsl::{exists, select};
+    use schema::form_ids::dsl::*;
+
+    select(exists(form_ids.filter(norm.eq(key))))
+      .get_result(&self.conn)
+      .unwrap()
+  }
+
+  // Databases generated without a glossary have no definitions (and older ones
+  // have no definitions table at all)
+  pub fn has_definitions(&self) -> bool {
+    use schema::definitions::dsl::*;
+
+    definitions.select(norm).first::<String>(&self.conn).is_ok()
+  }
+
+  pub fn get_definition(&self, key: &str) -> Option<String> {
+    use schema::definitions::dsl::*;
+
+    definitions
+      .filter(norm.eq(key))
+      .select(definition)
+      .first(&self.conn)
+      .ok()
+  }
+
+  // Only keys easy enough for the given difficulty are returned, unless the
+  // database has no set difficulties or none of the keys are easy enough
+  pub fn get_set_keys(
+    &self,
+    len_key: &usize,
+    level: Difficulty,
+  ) -> Vec<String> {
+    // Databases built without difficulties (or with none for this length)
+    // offer every key, but otherwise keys over the cap are left out even if
+    // that leaves nothing
+    if let Some(max) = level.max_set_difficulty() {
+      use schema::set_difficulties::dsl::*;
+
+      let rated = set_difficulties
+        .filter(len.eq(*len_key as i32))
+        .select((key, difficulty))
+        .order(key.asc())
+        .load::<(String, i32)>(&self.conn);
+
+      if let Ok(rated) = rated {
+        if !rated.is_empty() {
+          return rated
+            .into_iter()
+            .filter(|&(_, d)| d <= max)
+            .map(|(k, _)| k)
+            .collect();
+        }
+      }
+    }
+
     let results = {
       use schema::set_keys::dsl::*;
 
+      // Sorted so that a seeded Rng picks the same key from any copy of the
+      // database
       set_keys
         .filter(len.eq(*len_key as i32))
+        .order(key.asc())
         .load::<SetKeyQ>(&self.conn)
         .unwrap()
     };
@@ -61,6 +133,35 @@ impl WordList {
     results.into_iter().map(|r| r.key).collect()
   }
 
+  // Words missing from the result have no commonness score
+  pub fn get_commonness(&self, norms: &[String]) -> HashMap<String, i32> {
+    use schema::commonness::dsl::*;
+
+    commonness
+      .filter(norm.eq_any(norms))
+      .load::<CommonnessQ>(&self.conn)
+      .map(|r| r.into_iter().map(|c| (c.norm, c.score)).collect())
+      .unwrap_or_default()
+  }
+
+  // Each ladder's start word, target word and minimum number of steps
+  pub fn get_ladders(&self, len_key: &usize) -> Vec<(String, String, usize)> {
+    use schema::ladders::dsl::*;
+
+    // Sorted so that a seeded Rng picks the same ladder from any copy of the
+    // database
+    ladders
+      .filter(len.eq(*len_key as i32))
+      .order(oid.asc())
+      .load::<LadderQ>(&self.conn)
+      .map(|r| {
+        r.into_iter()
+          .map(|l| (l.start, l.target, l.steps as usize))
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+
   pub fn get_set(&self, key_str: &str) -> Vec<String> {
     let id_results = {
       use schema::set_ids::dsl::*;
@@ -96,3 +197,81 @@ pub struct WordlistForm {
   pub full: String,
   pub blanked: String,
 }
+
+#[cfg(any(test, feature = "test-support"))]
+impl WordList {
+  // A word list held in memory, for tests (including the ones outside the
+  // crate): one set under the given key, and any other words the dictionary
+  // should have
+  pub fn with_set(key: &str, set: &[&str], others: &[&str]) -> Self {
+    use diesel::{connection::SimpleConnection, insert_into};
+    use schema::{form_ids, forms, set_ids, set_keys, sets};
+
+    let ret = Self::new(":memory:");
+
+    ret
+      .conn
+      .batch_execute(include_str!(
+        "../tools/gen-words/migrations/2018-09-19-233955_init_wordlist/up.sql"
+      )).unwrap();
+
+    insert_into(set_ids::table)
+      .values((set_ids::key.eq(key), set_ids::id.eq(0)))
+      .execute(&ret.conn)
+      .unwrap();
+
+    insert_into(set_keys::table)
+      .values((
+        set_keys::oid.eq(0),
+        set_keys::len.eq(key.chars().count() as i32),
+        set_keys::key.eq(key),
+      )).execute(&ret.conn)
+      .unwrap();
+
+    for (i, word) in set.iter().chain(others).enumerate() {
+      insert_into(form_ids::table)
+        .values((form_ids::norm.eq(word), form_ids::id.eq(i as i32)))
+        .execute(&ret.conn)
+        .unwrap();
+
+      insert_into(forms::table)
+        .values((
+          forms::oid.eq(i as i32),
+          forms::id.eq(i as i32),
+          forms::blank.eq("_".repeat(word.chars().count())),
+          forms::full.eq(word),
+        )).execute(&ret.conn)
+        .unwrap();
+    }
+
+    for (i, word) in set.iter().enumerate() {
+      insert_into(sets::table)
+        .values((sets::oid.eq(i as i32), sets::id.eq(0), sets::norm.eq(word)))
+        .execute(&ret.conn)
+        .unwrap();
+    }
+
+    ret
+  }
+
+  // Gives the set from with_set a difficulty, as the word list generator
+  // would.  This creates the difficulty tables, so call it only once.
+  pub fn rate_set(&self, key: &str, difficulty: i32) {
+    use diesel::{connection::SimpleConnection, insert_into};
+    use schema::set_difficulties;
+
+    self
+      .conn
+      .batch_execute(include_str!(
+        "../tools/gen-words/migrations/2026-10-18-130000_add_difficulty/up.sql"
+      )).unwrap();
+
+    insert_into(set_difficulties::table)
+      .values((
+        set_difficulties::key.eq(key),
+        set_difficulties::len.eq(key.chars().count() as i32),
+        set_difficulties::difficulty.eq(difficulty),
+      )).execute(&self.conn)
+      .unwrap();
+  }
+}
@@ -0,0 +1,188 @@
+// Races two clients against a server over real sockets
+
+extern crate omelette;
+extern crate serde_json;
+
+use omelette::{
+  difficulty::Difficulty,
+  net::{ClientMessage, NetState, ServerMessage},
+  race, seed,
+  word_list::WordList,
+};
+use std::{
+  io::{prelude::*, BufReader},
+  net::{SocketAddr, TcpListener, TcpStream},
+  thread,
+  time::Duration,
+};
+
+struct Client {
+  stream: TcpStream,
+  reader: BufReader<TcpStream>,
+}
+
+impl Client {
+  fn connect(addr: SocketAddr) -> Self {
+    let stream = TcpStream::connect(addr).unwrap();
+
+    // A message that never comes fails the test instead of hanging it
+    stream
+      .set_read_timeout(Some(Duration::from_secs(5)))
+      .unwrap();
+
+    Self {
+      reader: BufReader::new(stream.try_clone().unwrap()),
+      stream,
+    }
+  }
+
+  fn send(&mut self, msg: &ClientMessage) {
+    writeln!(self.stream, "{}", serde_json::to_string(msg).unwrap()).unwrap();
+  }
+
+  fn recv(&mut self) -> ServerMessage {
+    let mut line = String::new();
+
+    self.reader.read_line(&mut line).unwrap();
+    serde_json::from_str(&line).unwrap()
+  }
+
+  fn recv_state(&mut self) -> NetState {
+    match self.recv() {
+      ServerMessage::State { state } => state,
+      _ => panic!("expected a state"),
+    }
+  }
+
+  // Returns the player and points
+  fn recv_claim(&mut self, word: &str) -> (String, u32) {
+    match self.recv() {
+      ServerMessage::Claim {
+        player,
+        word: w,
+        points,
+      } => {
+        assert_eq!(w, word);
+        (player, points)
+      }
+      _ => panic!("expected a claim"),
+    }
+  }
+
+  fn recv_guess(&mut self) -> (String, u32) {
+    match self.recv() {
+      ServerMessage::Guess { result, points, .. } => (result, points),
+      _ => panic!("expected a guess result"),
+    }
+  }
+
+  fn join(&mut self, name: &str) -> NetState {
+    self.send(&ClientMessage::Join { name: name.into() });
+    self.recv_state()
+  }
+
+  fn guess(&mut self, word: &str) {
+    self.send(&ClientMessage::Guess { word: word.into() });
+  }
+}
+
+fn serve() -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+  let addr = listener.local_addr().unwrap();
+
+  thread::spawn(move || {
+    let words = WordList::with_set(
+      "tones",
+      &["notes", "onset", "stone", "tones", "one", "ten"],
+      &["ton", "net"],
+    );
+
+    race::serve(
+      listener,
+      &words,
+      seed::rng_from_seed(0),
+      vec![5],
+      Difficulty::Hard,
+      |_| {},
+    );
+  });
+
+  addr
+}
+
+fn found_by(state: &NetState, word: &str) -> Option<String> {
+  state
+    .words
+    .iter()
+    .find(|w| w.word.as_ref().map_or(false, |v| v == word))
+    .and_then(|w| w.player.clone())
+}
+
+#[test]
+fn claims_go_to_everyone() {
+  let addr = serve();
+
+  let mut ann = Client::connect(addr);
+  let state = ann.join("ann");
+
+  assert_eq!(state.round, 1);
+  assert_eq!(state.words.len(), 6);
+  assert!(state.words.iter().all(|w| w.word.is_none()));
+
+  let mut bo = Client::connect(addr);
+  bo.join("bo");
+
+  // Everyone hears about whoever joins
+  assert_eq!(ann.recv_state().players.len(), 2);
+
+  ann.guess("stone");
+
+  assert_eq!(ann.recv_guess().0, "found");
+
+  let mut stone_points = Vec::new();
+
+  for client in &mut [&mut ann, &mut bo] {
+    let (player, points) = client.recv_claim("stone");
+
+    assert_eq!(player, "ann");
+    assert_eq!(found_by(&client.recv_state(), "stone"), Some("ann".into()));
+
+    stone_points.push(points);
+  }
+
+  bo.guess("ton");
+
+  assert_eq!(bo.recv_guess().0, "bonus");
+
+  let ann_claim = ann.recv_claim("ton");
+  let bo_claim = bo.recv_claim("ton");
+
+  assert_eq!(ann_claim, bo_claim);
+  assert_eq!(ann_claim.0, "bo");
+
+  let ann_state = ann.recv_state();
+  let bo_state = bo.recv_state();
+
+  assert_eq!(
+    serde_json::to_string(&ann_state).unwrap(),
+    serde_json::to_string(&bo_state).unwrap()
+  );
+
+  assert_eq!(ann_state.bonus.len(), 1);
+  assert_eq!(ann_state.bonus[0].player, "bo");
+
+  let players: Vec<_> = ann_state
+    .players
+    .iter()
+    .map(|p| (p.name.as_str(), p.points, p.found))
+    .collect();
+
+  assert_eq!(players[0], ("ann", stone_points[0], 1));
+  assert_eq!(players[1], ("bo", bo_claim.1, 1));
+  assert_eq!(stone_points[0], stone_points[1]);
+
+  // A word can only be claimed once
+  bo.guess("stone");
+
+  assert_eq!(bo.recv_guess(), ("already_found".into(), 0));
+}
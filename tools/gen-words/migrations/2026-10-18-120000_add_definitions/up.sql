@@ -0,0 +1,4 @@
+CREATE TABLE definitions (
+  norm       TEXT PRIMARY KEY NOT NULL,
+  definition TEXT NOT NULL
+);
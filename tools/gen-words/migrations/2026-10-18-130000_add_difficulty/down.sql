@@ -0,0 +1,2 @@
+DROP TABLE commonness;
+DROP TABLE set_difficulties;
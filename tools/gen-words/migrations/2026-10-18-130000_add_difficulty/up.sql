@@ -0,0 +1,10 @@
+CREATE TABLE commonness (
+  norm  TEXT PRIMARY KEY NOT NULL,
+  score INTEGER NOT NULL
+);
+
+CREATE TABLE set_difficulties (
+  key        TEXT PRIMARY KEY NOT NULL,
+  len        INTEGER NOT NULL,
+  difficulty INTEGER NOT NULL
+);
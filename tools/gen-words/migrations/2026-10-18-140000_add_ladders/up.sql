@@ -0,0 +1,7 @@
+CREATE TABLE ladders (
+  oid    INTEGER PRIMARY KEY,
+  len    INTEGER NOT NULL,
+  start  TEXT NOT NULL,
+  target TEXT NOT NULL,
+  steps  INTEGER NOT NULL
+) WITHOUT ROWID;
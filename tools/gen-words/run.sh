@@ -1,3 +1,4 @@
 #!/usr/bin/env sh
-diesel migration redo || diesel migration run
+# The database is regenerated from scratch, so start over with every migration
+diesel database reset
 cargo run --release $@
\ No newline at end of file
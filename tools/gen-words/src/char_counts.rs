@@ -0,0 +1,61 @@
+// NB: this file is shared with the game (via a symlink), so that the solver
+//     there agrees with the sets generated here
+
+use std::collections::HashMap;
+
+pub static MIN_VALID_LEN: usize = 3;
+pub static MAX_LEN_DIFFERENCE: usize = 5;
+
+pub type CharCounts = HashMap<char, usize>;
+
+pub fn count_chars(s: &str) -> CharCounts {
+  let mut ret = CharCounts::new();
+
+  for c in s.chars() {
+    use std::collections::hash_map::Entry::*;
+
+    match ret.entry(c) {
+      Occupied(o) => {
+        let val = o.into_mut();
+        *val = *val + 1;
+      }
+      Vacant(v) => {
+        v.insert(1);
+      }
+    }
+  }
+
+  ret
+}
+
+pub fn is_subseq(count: &CharCounts, of: &CharCounts) -> bool {
+  count.iter().all(|(c, n)| n <= of.get(c).unwrap_or(&0))
+}
+
+// Checks whether a word belongs in the set for a key, given both their lengths
+// and letter counts
+pub fn in_set(
+  len: usize,
+  count: &CharCounts,
+  key_len: usize,
+  key_count: &CharCounts,
+) -> bool {
+  len >= MIN_VALID_LEN
+    && len <= key_len
+    && (key_len < MAX_LEN_DIFFERENCE || len >= key_len - MAX_LEN_DIFFERENCE)
+    && is_subseq(count, key_count)
+}
+
+// Checks whether a word can become another in one ladder step, which changes a
+// single letter and may rearrange the rest
+pub fn is_ladder_step(count: &CharCounts, to: &CharCounts) -> bool {
+  let len: usize = count.values().sum();
+  let to_len: usize = to.values().sum();
+
+  let changed: usize = count
+    .iter()
+    .map(|(c, n)| n.saturating_sub(*to.get(c).unwrap_or(&0)))
+    .sum();
+
+  len == to_len && changed == 1
+}
@@ -8,10 +8,12 @@ extern crate error_chain;
 #[macro_use]
 extern crate lazy_static;
 
+mod char_counts;
 mod models;
 mod schema;
 mod thread_pool;
 
+use char_counts::*;
 use regex::Regex;
 use std::{
   collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
@@ -55,48 +57,34 @@ struct WordlistForm {
   full: String,
 }
 
-type CharCounts = HashMap<char, usize>;
-
-fn count_chars(s: &str) -> CharCounts {
-  let mut ret = CharCounts::new();
-
-  for c in s.chars() {
-    use std::collections::hash_map::Entry::*;
-
-    match ret.entry(c) {
-      Occupied(o) => {
-        let val = o.into_mut();
-        *val = *val + 1;
-      }
-      Vacant(v) => {
-        v.insert(1);
-      }
-    }
-  }
-
-  ret
-}
-
-fn is_subseq(count: &CharCounts, of: &CharCounts) -> bool {
-  count.iter().all(|(c, n)| n <= of.get(c).unwrap_or(&0))
-}
-
-static MIN_VALID_LEN: usize = 3;
 static MIN_LEN: usize = 4;
 static MAX_LEN: usize = 10;
-static MAX_LEN_DIFFERENCE: usize = 5;
+
+static LADDER_MIN_LEN: usize = 3;
+static LADDER_MAX_LEN: usize = 6;
+static LADDER_MIN_STEPS: usize = 3;
+static LADDER_MAX_STEPS: usize = 8;
 
 #[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 struct Normalized(String); // Used as a string with nonword characters stripped
 #[derive(Clone, PartialEq, PartialOrd, Eq, Ord, Hash)]
 struct Depermuted(String); // Used as a Normalized with its characters sorted
 
+fn normalize(word: &str) -> Normalized {
+  lazy_static! {
+    static ref NORMAL_RE: Regex = Regex::new(r"\W+").unwrap();
+  }
+
+  Normalized(NORMAL_RE.replace_all(&word.to_lowercase(), "").into_owned())
+}
+
 struct Stage1 {
   permutations: HashMap<Depermuted, HashSet<Normalized>>,
   counts: HashMap<Depermuted, CharCounts>,
   valid_subwords: HashSet<Depermuted>,
   len_groups: HashMap<usize, HashSet<Depermuted>>,
   forms: HashMap<Normalized, Vec<WordlistForm>>,
+  commonness: HashMap<Normalized, i32>, // Only for words that were given one
 }
 
 struct Stage2<'a> {
@@ -105,13 +93,26 @@ struct Stage2<'a> {
   used_words: HashSet<Normalized>,
 }
 
+struct Stage3 {
+  // The length, start, target and minimum number of steps of each ladder
+  ladders: Vec<(usize, Normalized, Normalized, usize)>,
+}
+
 fn stage_1(file: &str, blacklist_file: &str) -> Result<Stage1> {
-  let words: BTreeSet<_> = {
+  // Each line holds a word, optionally followed by a tab and its commonness
+  let words: BTreeMap<String, Option<i32>> = {
     let file = BufReader::new(File::open(file)?);
 
     file
       .lines()
-      .map(|l| l.unwrap().trim().to_string())
+      .map(|l| {
+        let l = l.unwrap();
+        let mut parts = l.splitn(2, '\t');
+        let word = parts.next().unwrap().trim().to_string();
+        let score = parts.next().and_then(|s| s.trim().parse().ok());
+
+        (word, score)
+      })
       .collect()
   };
 
@@ -146,40 +147,38 @@ fn stage_1(file: &str, blacklist_file: &str) -> Result<Stage1> {
   let mut valid_subwords: HashSet<Depermuted> = HashSet::new();
 
   let mut forms: HashMap<Normalized, Vec<WordlistForm>> = HashMap::new();
+  let mut commonness: HashMap<Normalized, i32> = HashMap::new();
 
   let mut used_blacklist: BTreeSet<Normalized> = BTreeSet::new();
 
   lazy_static! {
     static ref REJECT_RE: Regex = Regex::new(r"[\d\s]").unwrap();
-    static ref NORMAL_RE: Regex = Regex::new(r"\W+").unwrap();
     static ref BLANK_RE: Regex = Regex::new(r"[\w--\p{Lu}\p{Lt}]").unwrap();
     static ref BLANK_CAPS_RE: Regex = Regex::new(r"[\p{Lu}\p{Lt}]").unwrap();
   }
 
-  let blacklist: HashSet<_> = blacklist
-    .iter()
-    .map(|w| {
-      let lower = w.to_lowercase();
-      Normalized(NORMAL_RE.replace_all(&lower, "").into_owned())
-    })
-    .collect();
+  let blacklist: HashSet<_> = blacklist.iter().map(|w| normalize(w)).collect();
 
-  for word in words {
+  for (word, score) in words {
     use std::collections::hash_map::Entry::*;
 
     if REJECT_RE.is_match(&word) {
       continue;
     }
 
-    let normalized = word.to_lowercase();
-    let normalized =
-      Normalized(NORMAL_RE.replace_all(&normalized, "").into_owned());
+    let normalized = normalize(&word);
 
     if blacklist.contains(&normalized) {
       used_blacklist.insert(normalized.clone());
       continue;
     }
 
+    // A word with several forms is as common as its most common one
+    if let Some(score) = score {
+      let c = commonness.entry(normalized.clone()).or_insert(score);
+      *c = (*c).max(score);
+    }
+
     let blank = BLANK_RE.replace_all(&word, "_");
     let blank = BLANK_CAPS_RE.replace_all(&blank, "_").into_owned(); // TODO: highlight this somehow?
 
@@ -200,7 +199,7 @@ fn stage_1(file: &str, blacklist_file: &str) -> Result<Stage1> {
         v.insert(HashSet::new()).insert(normalized);
         counts.insert(depermuted.clone(), count_chars(&depermuted.0));
 
-        match len_groups.entry(depermuted.0.len()) {
+        match len_groups.entry(depermuted.0.chars().count()) {
           Vacant(v) => {
             v.insert(HashSet::new()).insert(depermuted.clone());
           }
@@ -209,7 +208,7 @@ fn stage_1(file: &str, blacklist_file: &str) -> Result<Stage1> {
           }
         }
 
-        if depermuted.0.len() >= MIN_VALID_LEN {
+        if depermuted.0.chars().count() >= MIN_VALID_LEN {
           valid_subwords.insert(depermuted);
         }
       }
@@ -220,6 +219,7 @@ fn stage_1(file: &str, blacklist_file: &str) -> Result<Stage1> {
   }
 
   println!("{} normalized", forms.len());
+  println!("{} with a commonness score", commonness.len());
   println!("{} depermuted", permutations.len());
   println!("{} valid subword(s)", valid_subwords.len());
 
@@ -296,9 +296,90 @@ fn stage_1(file: &str, blacklist_file: &str) -> Result<Stage1> {
     valid_subwords,
     len_groups,
     forms,
+    commonness,
   })
 }
 
+// Reads a glossary of tab-separated word/definition pairs, keeping only words
+// found in the wordlist.  Words listed more than once have their definitions
+// joined together.
+fn read_glossary(
+  file: &str,
+  forms: &HashMap<Normalized, Vec<WordlistForm>>,
+) -> Result<BTreeMap<Normalized, String>> {
+  let file = BufReader::new(File::open(file)?);
+
+  let mut ret: BTreeMap<Normalized, String> = BTreeMap::new();
+  let mut read = 0;
+  let mut skipped = 0;
+
+  for line in file.lines() {
+    use std::collections::btree_map::Entry::*;
+
+    let line = line?;
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let (word, def) = match line.find('\t') {
+      Some(i) => (&line[..i], line[i + 1..].trim()),
+      None => {
+        skipped = skipped + 1;
+        continue;
+      }
+    };
+
+    let norm = normalize(word);
+
+    if def.is_empty() || !forms.contains_key(&norm) {
+      skipped = skipped + 1;
+      continue;
+    }
+
+    read = read + 1;
+
+    match ret.entry(norm) {
+      Vacant(v) => {
+        v.insert(def.to_string());
+      }
+      Occupied(o) => {
+        let o = o.into_mut();
+        o.push_str("; ");
+        o.push_str(def);
+      }
+    }
+  }
+
+  println!(
+    "read {} definition(s) for {} word(s), {} skipped",
+    read,
+    ret.len(),
+    skipped
+  );
+
+  Ok(ret)
+}
+
+// Rates a set from 0 (easy) to 100 (hard) by how uncommon its words are on
+// average, or returns None if none of them have a commonness score
+fn set_difficulty(
+  set: &[Normalized],
+  commonness: &HashMap<Normalized, i32>,
+) -> Option<i32> {
+  let scores: Vec<_> = set.iter().filter_map(|n| commonness.get(n)).collect();
+
+  if scores.is_empty() {
+    return None;
+  }
+
+  let mean =
+    scores.iter().map(|s| **s as f64).sum::<f64>() / scores.len() as f64;
+
+  Some(100 - mean.round() as i32)
+}
+
 fn stage_2<'a>(s1: &'a Arc<Stage1>) -> Result<Stage2<'a>> {
   let mut sets: HashMap<Depermuted, Vec<Normalized>> = HashMap::new(); // TODO: can I go back to borrowing inside the vec?
   let mut set_keys: HashMap<usize, Vec<&Depermuted>> = HashMap::new();
@@ -334,15 +415,21 @@ fn stage_2<'a>(s1: &'a Arc<Stage1>) -> Result<Stage2<'a>> {
         .valid_subwords
         .iter()
         .filter(|deperm2| {
-          deperm2.0.len() <= depermuted.0.len()
-            && (depermuted.0.len() < MAX_LEN_DIFFERENCE
-              || deperm2.0.len() >= depermuted.0.len() - MAX_LEN_DIFFERENCE)
-            && is_subseq(&s1.counts[*deperm2], &count)
+          in_set(
+            deperm2.0.chars().count(),
+            &s1.counts[*deperm2],
+            depermuted.0.chars().count(),
+            &count,
+          )
         })
         .flat_map(|d| s1.permutations[d].clone()) // TODO: can I go back to borrowing this?
         .collect();
 
-      list.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then(a.0.cmp(&b.0)));
+      list.sort_by(|a, b| {
+        let (a_len, b_len) = (a.0.chars().count(), b.0.chars().count());
+
+        a_len.cmp(&b_len).then(a.0.cmp(&b.0))
+      });
 
       set_tx
         .send((depermuted, list))
@@ -390,6 +477,126 @@ fn stage_2<'a>(s1: &'a Arc<Stage1>) -> Result<Stage2<'a>> {
   })
 }
 
+// Picks the word to show for a group of anagrams: the most common one, or the
+// first alphabetically if they're equally common
+fn ladder_word(s1: &Stage1, depermuted: &Depermuted) -> Normalized {
+  let score = |n: &Normalized| *s1.commonness.get(n).unwrap_or(&0);
+
+  s1.permutations[depermuted]
+    .iter()
+    .max_by(|a, b| score(a).cmp(&score(b)).then_with(|| b.0.cmp(&a.0)))
+    .unwrap()
+    .clone()
+}
+
+// Finds word ladders, pairing each word with the farthest word it can reach.
+// Since a ladder step can rearrange letters freely, a group of anagrams is
+// treated as a single word.
+fn stage_3(s1: &Stage1) -> Result<Stage3> {
+  let mut ladders = Vec::new();
+
+  let start = Instant::now();
+
+  for len in LADDER_MIN_LEN..LADDER_MAX_LEN + 1 {
+    let group = match s1.len_groups.get(&len) {
+      Some(g) => g,
+      None => continue,
+    };
+
+    // Sorted so that a wordlist always produces the same ladders
+    let mut keys: Vec<&Depermuted> = group.iter().collect();
+    keys.sort();
+
+    let letters: BTreeSet<char> =
+      keys.iter().flat_map(|d| d.0.chars()).collect();
+
+    let mut edges: HashMap<&Depermuted, Vec<&Depermuted>> = HashMap::new();
+
+    for depermuted in &keys {
+      let chars: Vec<char> = depermuted.0.chars().collect();
+      let mut tos: BTreeSet<&Depermuted> = BTreeSet::new();
+
+      for i in 0..chars.len() {
+        for c in &letters {
+          if *c == chars[i] {
+            continue;
+          }
+
+          let mut to = chars.clone();
+          to[i] = *c;
+          to.sort();
+
+          if let Some(to) = group.get(&Depermuted(to.into_iter().collect())) {
+            tos.insert(to);
+          }
+        }
+      }
+
+      edges.insert(depermuted, tos.into_iter().collect());
+    }
+
+    for (i, from) in keys.iter().enumerate() {
+      if i % 100 == 0 {
+        print!("\r\x1b[2K(len {}: {}/{}) {}", len, i, keys.len(), &from.0);
+        io::stdout().flush().unwrap();
+      }
+
+      let mut seen: HashSet<&Depermuted> = HashSet::new();
+      let mut frontier = vec![*from];
+      let mut steps = 0;
+
+      seen.insert(from);
+
+      while steps < LADDER_MAX_STEPS {
+        let mut next = Vec::new();
+
+        for depermuted in &frontier {
+          for to in &edges[depermuted] {
+            if seen.insert(to) {
+              next.push(*to);
+            }
+          }
+        }
+
+        if next.is_empty() {
+          break;
+        }
+
+        frontier = next;
+        steps = steps + 1;
+      }
+
+      if steps < LADDER_MIN_STEPS {
+        continue;
+      }
+
+      let target = frontier
+        .iter()
+        .map(|d| ladder_word(s1, d))
+        .max_by(|a, b| {
+          let score = |n: &Normalized| *s1.commonness.get(n).unwrap_or(&0);
+
+          score(a).cmp(&score(b)).then_with(|| b.0.cmp(&a.0))
+        })
+        .unwrap();
+
+      ladders.push((len, ladder_word(s1, from), target, steps));
+    }
+  }
+
+  let end = Instant::now();
+  let time = end - start;
+
+  println!(
+    "\r\x1b[2K{} ladder(s) found in {}.{:02}s",
+    ladders.len(),
+    time.as_secs(),
+    time.subsec_millis() / 10
+  );
+
+  Ok(Stage3 { ladders })
+}
+
 fn run() -> Result<()> {
   let mut args: VecDeque<_> = env::args().collect();
   args.pop_front(); // drop argv[0]
@@ -412,11 +619,24 @@ fn run() -> Result<()> {
 
   let s1 = Arc::new(stage_1(&file, "etc/blacklist.txt")?);
 
+  let glossary = match args.pop_front() {
+    Some(g) => read_glossary(&g, &s1.forms)?,
+    None => BTreeMap::new(),
+  };
+
   let s2 = stage_2(&s1)?;
 
-  let mut forms = s1.forms.clone();
+  let s3 = stage_3(&s1)?;
 
-  forms.retain(|k, _| s2.used_words.contains(k));
+  // NB: forms are kept even if they aren't used in any set so that the game can
+  //     recognize valid words that aren't part of the current set
+  let forms = &s1.forms;
+
+  println!(
+    "{} of {} normalized word(s) used in sets",
+    s2.used_words.len(),
+    forms.len()
+  );
 
   {
     use diesel::{insert_into, prelude::*, sqlite::SqliteConnection};
@@ -430,6 +650,10 @@ fn run() -> Result<()> {
     let mut insert_set_ids: Vec<SetId> = Vec::new();
     let mut insert_sets: Vec<Set> = Vec::new();
     let mut insert_set_keys: Vec<SetKey> = Vec::new();
+    let mut insert_definitions: Vec<Definition> = Vec::new();
+    let mut insert_commonness: Vec<Commonness> = Vec::new();
+    let mut insert_set_difficulties: Vec<SetDifficulty> = Vec::new();
+    let mut insert_ladders: Vec<Ladder> = Vec::new();
 
     for (i, (norm, forms)) in forms.iter().enumerate() {
       insert_form_ids.push(FormId {
@@ -475,6 +699,43 @@ fn run() -> Result<()> {
       }
     }
 
+    for (norm, score) in &s1.commonness {
+      insert_commonness.push(Commonness {
+        norm: &norm.0,
+        score: *score,
+      });
+    }
+
+    for (len, deperms) in &s2.set_keys {
+      for deperm in deperms {
+        if let Some(d) = set_difficulty(&s2.sets[*deperm], &s1.commonness) {
+          insert_set_difficulties.push(SetDifficulty {
+            key: &deperm.0,
+            len: *len as i32,
+            difficulty: d,
+          });
+        }
+      }
+    }
+
+    for &(len, ref start, ref target, steps) in &s3.ladders {
+      let oid = insert_ladders.len() as i32;
+      insert_ladders.push(Ladder {
+        oid,
+        len: len as i32,
+        start: &start.0,
+        target: &target.0,
+        steps: steps as i32,
+      });
+    }
+
+    for (norm, def) in &glossary {
+      insert_definitions.push(Definition {
+        norm: &norm.0,
+        definition: def,
+      });
+    }
+
     println!("committing to database...");
 
     dotenv().ok();
@@ -486,8 +747,9 @@ fn run() -> Result<()> {
 
     {
       use schema::{
-        form_ids::dsl::*, forms::dsl::*, set_ids::dsl::*, set_keys::dsl::*,
-        sets::dsl::*,
+        commonness::dsl::*, definitions::dsl::*, form_ids::dsl::*,
+        forms::dsl::*, ladders::dsl::*, set_difficulties::dsl::*,
+        set_ids::dsl::*, set_keys::dsl::*, sets::dsl::*,
       };
 
       println!("  form_ids");
@@ -508,6 +770,24 @@ fn run() -> Result<()> {
       insert_into(set_keys)
         .values(&insert_set_keys)
         .execute(&conn)?;
+
+      println!("  commonness");
+      insert_into(commonness)
+        .values(&insert_commonness)
+        .execute(&conn)?;
+
+      println!("  set_difficulties");
+      insert_into(set_difficulties)
+        .values(&insert_set_difficulties)
+        .execute(&conn)?;
+
+      println!("  ladders");
+      insert_into(ladders).values(&insert_ladders).execute(&conn)?;
+
+      println!("  definitions");
+      insert_into(definitions)
+        .values(&insert_definitions)
+        .execute(&conn)?;
     }
 
     let end = Instant::now();
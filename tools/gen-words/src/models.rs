@@ -72,3 +72,63 @@ pub struct SetKeyQ {
   pub len: i32,
   pub key: String,
 }
+
+#[derive(Insertable)]
+#[table_name = "definitions"]
+pub struct Definition<'a> {
+  pub norm: &'a str,
+  pub definition: &'a str,
+}
+
+#[derive(Queryable)]
+pub struct DefinitionQ {
+  pub norm: String,
+  pub definition: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "commonness"]
+pub struct Commonness<'a> {
+  pub norm: &'a str,
+  pub score: i32,
+}
+
+#[derive(Queryable)]
+pub struct CommonnessQ {
+  pub norm: String,
+  pub score: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "set_difficulties"]
+pub struct SetDifficulty<'a> {
+  pub key: &'a str,
+  pub len: i32,
+  pub difficulty: i32,
+}
+
+#[derive(Queryable)]
+pub struct SetDifficultyQ {
+  pub key: String,
+  pub len: i32,
+  pub difficulty: i32,
+}
+
+#[derive(Insertable)]
+#[table_name = "ladders"]
+pub struct Ladder<'a> {
+  pub oid: i32,
+  pub len: i32,
+  pub start: &'a str,
+  pub target: &'a str,
+  pub steps: i32,
+}
+
+#[derive(Queryable)]
+pub struct LadderQ {
+  pub oid: i32,
+  pub len: i32,
+  pub start: String,
+  pub target: String,
+  pub steps: i32,
+}